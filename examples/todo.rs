@@ -22,7 +22,7 @@ async fn main() -> OxideTodoResult<()> {
     println!("Todo created: {todo:#?}");
 
     // Cancel the todo by update its status. (you can also update the title using `Todo::set_title`).
-    let todo = todo.set_status(TodoStatus::Cancelled).await?;
+    let todo = todo.set_status(TodoStatus::Cancelled).update().await?;
     println!("Todo cancelled: {todo:#?}");
 
     // Delete the todo.
@@ -15,7 +15,7 @@ async fn main() -> OxideResult<()> {
         .await?
         .iter()
         .for_each(|todo| {
-            println!(" - {}", todo.title().unwrap());
+            println!(" - {}", todo.title());
         });
     // Print the homeworks that in progress.
     println!("- Homeworks in progress: ");
@@ -25,7 +25,7 @@ async fn main() -> OxideResult<()> {
         .await?
         .iter()
         .for_each(|todo| {
-            println!(" - {}", todo.title().unwrap());
+            println!(" - {}", todo.title());
         });
     // Print the todos that are pinding, and contains 'issue' in the title, ordered from older to newer.
     println!("- Todos in progress with 'issue' in the title: ");
@@ -36,7 +36,7 @@ async fn main() -> OxideResult<()> {
         .await?
         .iter()
         .for_each(|todo| {
-            println!(" - {}", todo.title().unwrap());
+            println!(" - {}", todo.title());
         });
 
     // Delete all the todos.
@@ -1,9 +1,11 @@
 use std::{
     future::{Future, IntoFuture},
     pin::Pin,
+    time::Duration,
 };
 
 use crate::{
+    cache::Cache,
     errors::{Error, ErrorMessage, Result as OxideResult},
     types::{TodoStatus, Todos},
 };
@@ -39,6 +41,16 @@ pub async fn response_result<T: for<'a> serde::Deserialize<'a>>(
     }
 }
 
+/// The timeouts of the requests, `None` for no timeout.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Timeouts {
+    /// The timeout of the whole request, from connecting until the response body is read.
+    pub request: Option<Duration>,
+    /// The timeout of the connect phase.
+    pub connect: Option<Duration>,
+}
+
 /// A list of all the endpoints of the server. With it's metadata.
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub enum Endpoints<'a> {
@@ -158,9 +170,8 @@ impl<'a> Endpoints<'a> {
         }
     }
 
-    /// Add a query to the request if the endpoint requires a query.
-    /// This will return the request builder with the query added.
-    pub fn add_query(&self, req: RequestBuilder) -> RequestBuilder {
+    /// Returns the query parameters of the endpoint, empty if the endpoint doesn't require a query.
+    pub fn query(&self) -> Vec<(&'static str, String)> {
         match self {
             Self::GetTodos(Todos {
                 limit,
@@ -171,23 +182,106 @@ impl<'a> Endpoints<'a> {
                 title,
                 ..
             }) => {
-                let mut req = req.query(&[
+                let mut query = vec![
                     ("limit", limit.to_string()),
                     ("offset", offset.to_string()),
                     ("order", order.to_string()),
                     ("order_by", order_by.to_string()),
-                ]);
+                ];
                 if let Some(status) = status {
-                    req = req.query(&[("status", status.to_string())]);
+                    query.push(("status", status.to_string()));
                 };
                 if let Some(title) = title {
-                    req = req.query(&[("title", title.to_string())]);
+                    query.push(("title", title.to_string()));
                 };
-                req
+                query
             }
-            _ => req,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Add a query to the request if the endpoint requires a query.
+    /// This will return the request builder with the query added.
+    pub fn add_query(&self, req: RequestBuilder) -> RequestBuilder {
+        let query = self.query();
+        if query.is_empty() {
+            req
+        } else {
+            req.query(&query)
         }
     }
+
+    /// Returns the cache key of the endpoint, the method and the uri with the query.
+    ///
+    /// The key doesn't include the token, the cache entries are stored by the token and this key.
+    pub fn cache_key(&self) -> String {
+        let uri = reqwest::Url::parse_with_params(&self.uri(), &self.query())
+            .map(String::from)
+            .unwrap_or_else(|_| self.uri());
+        format!("{} {uri}", self.method())
+    }
+
+    /// Send the request through the cache, if there is a cache.
+    /// - The `GET` endpoints will be returned from the cache if they are cached, else they will be cached after the request.
+    /// - The todo endpoints that change the todos will invalidate the cached responses of the user if they succeed.
+    pub async fn send_cached(
+        self,
+        cache: Option<&Cache>,
+        timeouts: Timeouts,
+    ) -> OxideResult<serde_json::Value> {
+        let (cache, token) = match (cache, self.token()) {
+            (Some(cache), Some(token)) => (cache, token.to_owned()),
+            _ => return self.send(timeouts).await,
+        };
+        match self {
+            Self::GetTodo { .. } | Self::GetTodos(_) => {
+                let key = self.cache_key();
+                if let Some(value) = cache.get(&token, &key) {
+                    return Ok(value);
+                }
+                // The user todos may change while the request is sent, the response is only cached if they didn't
+                let generation = cache.generation(&token);
+                let value = self.send(timeouts).await?;
+                cache.insert_if_unchanged(generation, token, key, value.clone());
+                Ok(value)
+            }
+            Self::CreateTodo { .. }
+            | Self::UpdateTodo { .. }
+            | Self::DeleteTodo { .. }
+            | Self::DeleteTodos { .. } => {
+                let value = self.send(timeouts).await?;
+                cache.invalidate_user(token);
+                Ok(value)
+            }
+            _ => self.send(timeouts).await,
+        }
+    }
+
+    /// Send the request with the timeouts.
+    pub async fn send(self, timeouts: Timeouts) -> OxideResult<serde_json::Value> {
+        let mut client = reqwest::Client::builder();
+        if let Some(connect) = timeouts.connect {
+            client = client.connect_timeout(connect);
+        }
+        let mut req = self.add_body(
+            client
+                .build()
+                .map_err(Error::ReqwestError)?
+                .request(self.method(), self.uri()),
+        );
+        if let Some(timeout) = timeouts.request {
+            req = req.timeout(timeout);
+        }
+        // All the endpoints require the user to be logged in except the register and login endpoints.
+        response_result(
+            add_token(self.add_query(req), self.token())
+                .await
+                .send()
+                .await
+                .map_err(Error::ReqwestError)?,
+        )
+        .await
+    }
 }
 
 impl<'a> IntoFuture for Endpoints<'a> {
@@ -195,17 +289,6 @@ impl<'a> IntoFuture for Endpoints<'a> {
     type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'a>>;
 
     fn into_future(self) -> Self::IntoFuture {
-        Box::pin(async move {
-            let req = self.add_body(reqwest::Client::new().request(self.method(), self.uri()));
-            // All the endpoints require the user to be logged in except the register and login endpoints.
-            response_result(
-                add_token(self.add_query(req), self.token())
-                    .await
-                    .send()
-                    .await
-                    .map_err(Error::ReqwestError)?,
-            )
-            .await
-        })
+        Box::pin(self.send(Timeouts::default()))
     }
 }
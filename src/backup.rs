@@ -0,0 +1,255 @@
+//! The backup module. This module contains the full backup of the user todos, and its restore to another server.
+//!
+//! A backup is created with [`User::backup`], it can be written to a file with [`Backup::to_writer`] and read
+//! back with [`Backup::from_reader`], then restored with [`User::restore`], with a client of any server.
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::backup::RestoreMode;
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let staging = Client::new("http://staging:8080").login("username", "password").await?;
+//!     let production = Client::new("http://production:8080").login("username", "password").await?;
+//!     let backup = staging.backup().await?;
+//!     let report = production.restore(&backup, RestoreMode::Replace).await?;
+//!     for (old, new) in report.mapping() {
+//!         println!("{old} -> {new}");
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`User::backup`]: crate::types::User::backup
+//! [`User::restore`]: crate::types::User::restore
+
+use crate::{
+    errors::{Error, Result as OxideResult},
+    types::{Todo, TodoStatus, User, PAGE_SIZE},
+};
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+/// The version of the backup format.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// A todo in a backup, with its original uuid and timestamps.
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ArchivedTodo {
+    /// The original todo uuid.
+    pub(crate) uuid: Uuid,
+    /// The todo title.
+    pub(crate) title: String,
+    /// The todo status.
+    pub(crate) status: TodoStatus,
+    /// Todo creation time. (Unix timestamp in seconds)
+    pub(crate) created_at: u64,
+    /// Last todo update time. (Unix timestamp in seconds)
+    pub(crate) updated_at: u64,
+}
+
+/// A versioned backup of all the user todos.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Backup {
+    /// The version of the backup format.
+    pub(crate) version: u32,
+    /// The server which the backup is created from.
+    pub(crate) server: String,
+    /// The username of the user, if it's known.
+    pub(crate) username: Option<String>,
+    /// When the backup is created. (Unix timestamp in seconds)
+    pub(crate) created_at: u64,
+    /// The todos, newest first.
+    pub(crate) todos: Vec<ArchivedTodo>,
+}
+
+/// The restore mode.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum RestoreMode {
+    /// Keep the existing todos, and create the backup todos which don't exist. A backup todo exists if there is
+    /// an existing todo with the same title and status, so restoring the same backup twice will not duplicate the todos.
+    Merge,
+    /// Delete all the existing todos with [`User::delete_all_todos`], then create the backup todos.
+    ///
+    /// [`User::delete_all_todos`]: crate::types::User::delete_all_todos
+    Replace,
+}
+
+/// The report of a restore.
+#[derive(Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct RestoreReport {
+    /// The uuids of the restored todos, from the original uuid to the uuid on the server.
+    pub(crate) mapping: HashMap<Uuid, Uuid>,
+    /// The todos that failed to be restored, with the error.
+    pub(crate) failed: Vec<(ArchivedTodo, Error)>,
+}
+
+impl ArchivedTodo {
+    /// Returns the original uuid of the todo.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Returns the title of the todo.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the status of the todo.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status
+    }
+
+    /// Returns the original creation time of the todo, as a Unix timestamp in seconds.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the original last update time of the todo, as a Unix timestamp in seconds.
+    pub fn updated_at(&self) -> u64 {
+        self.updated_at
+    }
+}
+
+impl From<Todo> for ArchivedTodo {
+    fn from(todo: Todo) -> Self {
+        Self {
+            uuid: todo.uuid,
+            title: todo.title,
+            status: todo.status,
+            created_at: todo.created_at,
+            updated_at: todo.updated_at,
+        }
+    }
+}
+
+impl Backup {
+    /// Returns the version of the backup format.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the server which the backup is created from.
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Returns the username of the user, if it's known.
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// Returns when the backup is created, as a Unix timestamp in seconds.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the todos of the backup, newest first.
+    pub fn todos(&self) -> &[ArchivedTodo] {
+        &self.todos
+    }
+
+    /// Write the backup as JSON to the writer.
+    pub fn to_writer(&self, writer: impl Write) -> OxideResult<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(From::from)
+    }
+
+    /// Read a JSON backup from the reader, returns an error if the backup version is not supported.
+    pub fn from_reader(reader: impl Read) -> OxideResult<Self> {
+        let backup: Self = serde_json::from_reader(reader)?;
+        if backup.version != BACKUP_VERSION {
+            return Err(Error::JSONError(serde::de::Error::custom(format!(
+                "unsupported backup version {}, expected {BACKUP_VERSION}",
+                backup.version
+            ))));
+        }
+        Ok(backup)
+    }
+}
+
+impl RestoreReport {
+    /// Returns the uuids of the restored todos, from the original uuid to the uuid on the server.
+    pub fn mapping(&self) -> &HashMap<Uuid, Uuid> {
+        &self.mapping
+    }
+
+    /// Returns the todos that failed to be restored, with the error.
+    pub fn failed(&self) -> &[(ArchivedTodo, Error)] {
+        &self.failed
+    }
+}
+
+impl User {
+    /// Returns a backup of all the user todos. See the [`backup`] module.
+    ///
+    /// [`backup`]: crate::backup
+    pub async fn backup(&self) -> OxideResult<Backup> {
+        let todos = self.todos().limit(PAGE_SIZE).all().await?;
+        Ok(Backup {
+            version: BACKUP_VERSION,
+            server: self.base_url.clone(),
+            username: self.name.clone(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            todos: todos.into_iter().map(ArchivedTodo::from).collect(),
+        })
+    }
+
+    /// Restore the backup todos to this user, with the given mode. See the [`backup`] module.
+    ///
+    /// The todos are created oldest first, so they keep their order. The server sets new uuids and timestamps,
+    /// use [`RestoreReport::mapping`] to find the new uuids. A todo that fails to be restored doesn't stop the restore.
+    ///
+    /// [`backup`]: crate::backup
+    pub async fn restore(&self, backup: &Backup, mode: RestoreMode) -> OxideResult<RestoreReport> {
+        let existing = match mode {
+            RestoreMode::Merge => self.todos().limit(PAGE_SIZE).all().await?,
+            RestoreMode::Replace => {
+                self.delete_all_todos().await?;
+                Vec::new()
+            }
+        };
+        // The existing todos by title and status, each existing todo matches one backup todo
+        let mut existing =
+            existing
+                .into_iter()
+                .fold(HashMap::<_, Vec<Uuid>>::new(), |mut existing, todo| {
+                    existing
+                        .entry((todo.title, todo.status))
+                        .or_default()
+                        .push(todo.uuid);
+                    existing
+                });
+        let mut report = RestoreReport::default();
+        for archived in backup.todos.iter().rev() {
+            if let Some(uuid) = existing
+                .get_mut(&(archived.title.clone(), archived.status.clone()))
+                .and_then(Vec::pop)
+            {
+                report.mapping.insert(archived.uuid, uuid);
+                continue;
+            }
+            match self
+                .create_todo(&archived.title)
+                .set_status(archived.status.clone())
+                .await
+            {
+                Ok(todo) => {
+                    report.mapping.insert(archived.uuid, todo.uuid);
+                }
+                Err(err) => report.failed.push((archived.clone(), err)),
+            }
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,416 @@
+//! The `oxide-todo-tui` terminal UI of the Oxide Todo API. (Requires the `tui` feature)
+//!
+//! The user is read from the `OXIDE_TODO_PROFILE` profile of the config file if it's set, else from
+//! `OXIDE_TODO_URL` and `OXIDE_TODO_TOKEN` (see `Client::from_env`), else from the session of the `oxide-todo`
+//! command-line client. The session token is only used with the session server.
+
+#![forbid(unsafe_code)]
+
+use crossterm::{
+    cursor::Show,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use oxide_todo_sdk::{
+    config::{session_path, TOKEN_ENV, URL_ENV},
+    errors::{Result as OxideResult, SDKError},
+    types::{Session, Todo, TodoStatus, User},
+    Client,
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+use std::{env, io, mem, panic, process::ExitCode};
+
+/// The default server url, when there is no `OXIDE_TODO_URL` and no session.
+const DEFAULT_URL: &str = "http://localhost:8080";
+/// The environment variable of the config file profile.
+const PROFILE_ENV: &str = "OXIDE_TODO_PROFILE";
+/// The amount of todos in a page.
+const PAGE_SIZE: usize = 20;
+/// The keyboard shortcuts, shown when there is no message.
+const HELP: &str = "a add  e edit  s status  d delete  f status filter  / title filter  \u{2190}/\u{2192} page  r refresh  q quit";
+
+/// Restores the terminal when it's dropped, after the UI returns or fails.
+struct TerminalGuard;
+
+/// The input mode of the UI.
+enum Mode {
+    /// Navigate the todos with the keyboard shortcuts.
+    Normal,
+    /// Type the title of a new todo.
+    Add(String),
+    /// Edit the title of the selected todo.
+    Edit(String),
+    /// Type the title filter.
+    Filter(String),
+    /// Confirm the deletion of the selected todo.
+    ConfirmDelete,
+}
+
+/// The state of the UI.
+struct App {
+    /// The logged in user.
+    user: User,
+    /// The todos of the current page.
+    todos: Vec<Todo>,
+    /// The selected todo of the page.
+    list: ListState,
+    /// The offset of the current page.
+    offset: usize,
+    /// Only the todos with this status.
+    status: Option<TodoStatus>,
+    /// Only the todos which contain this title, empty for all the todos.
+    title: String,
+    /// The input mode.
+    mode: Mode,
+    /// The result of the last action, shown instead of the help.
+    message: String,
+    /// `true` when the user wants to quit.
+    quit: bool,
+}
+
+impl App {
+    /// Create the UI state of the user, call [`App::refresh`] to fetch the first page.
+    fn new(user: User) -> Self {
+        Self {
+            user,
+            todos: Vec::new(),
+            list: ListState::default(),
+            offset: 0,
+            status: None,
+            title: String::new(),
+            mode: Mode::Normal,
+            message: String::new(),
+            quit: false,
+        }
+    }
+
+    /// Fetch the current page with the filters, and keep the selection in the page.
+    async fn refresh(&mut self) -> OxideResult<()> {
+        let mut todos = self.user.todos().offset(self.offset).limit(PAGE_SIZE);
+        if let Some(status) = &self.status {
+            todos = todos.status(status.clone());
+        }
+        if !self.title.is_empty() {
+            todos = todos.title(&self.title);
+        }
+        self.todos = todos.await?;
+        let selected = match self.todos.len() {
+            0 => None,
+            len => Some(self.list.selected().unwrap_or_default().min(len - 1)),
+        };
+        self.list.select(selected);
+        Ok(())
+    }
+
+    /// Returns the selected todo.
+    fn selected(&self) -> Option<&Todo> {
+        self.list.selected().and_then(|idx| self.todos.get(idx))
+    }
+
+    /// Draw and handle the key events until the user quits.
+    async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> OxideResult<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = read_event().await? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                self.message.clear();
+                if let Err(err) = self.on_key(key).await {
+                    self.message = format!("error: {err}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle the key event with the current mode.
+    async fn on_key(&mut self, key: KeyEvent) -> OxideResult<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return Ok(());
+        }
+        match mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.on_normal_key(key.code).await,
+            Mode::ConfirmDelete => {
+                if let (KeyCode::Char('y'), Some(todo)) = (key.code, self.selected()) {
+                    let todo = todo.clone();
+                    let title = todo.title().to_owned();
+                    todo.delete().await?;
+                    self.message = format!("Deleted `{title}`");
+                    self.refresh().await?;
+                }
+                Ok(())
+            }
+            mode => self.on_input_key(mode, key.code).await,
+        }
+    }
+
+    /// Handle the keyboard shortcuts of the normal mode.
+    async fn on_normal_key(&mut self, code: KeyCode) -> OxideResult<()> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select_next(1),
+            KeyCode::Up | KeyCode::Char('k') => self.select_next(-1),
+            KeyCode::Right | KeyCode::Char('n') if self.todos.len() == PAGE_SIZE => {
+                self.offset += PAGE_SIZE;
+                self.list.select(Some(0));
+                self.refresh().await?;
+            }
+            KeyCode::Left | KeyCode::Char('p') if self.offset > 0 => {
+                self.offset = self.offset.saturating_sub(PAGE_SIZE);
+                self.list.select(Some(0));
+                self.refresh().await?;
+            }
+            KeyCode::Char('r') => self.refresh().await?,
+            KeyCode::Char('s') => {
+                if let Some(todo) = self.selected() {
+                    todo.set_status(next_status(todo.status())).update().await?;
+                    self.refresh().await?;
+                }
+            }
+            KeyCode::Char('f') => {
+                self.status = match &self.status {
+                    None => Some(TodoStatus::Pending),
+                    Some(TodoStatus::Cancelled) => None,
+                    Some(status) => Some(next_status(status)),
+                };
+                self.offset = 0;
+                self.refresh().await?;
+            }
+            KeyCode::Char('/') => self.mode = Mode::Filter(self.title.clone()),
+            KeyCode::Char('a') => self.mode = Mode::Add(String::new()),
+            KeyCode::Char('e') => {
+                if let Some(todo) = self.selected() {
+                    self.mode = Mode::Edit(todo.title().to_owned());
+                }
+            }
+            KeyCode::Char('d') if self.selected().is_some() => self.mode = Mode::ConfirmDelete,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Handle the key of the input modes, `Enter` submits the input and `Esc` cancels it.
+    async fn on_input_key(&mut self, mut mode: Mode, code: KeyCode) -> OxideResult<()> {
+        let input = match &mut mode {
+            Mode::Add(input) | Mode::Edit(input) | Mode::Filter(input) => input,
+            Mode::Normal | Mode::ConfirmDelete => return Ok(()),
+        };
+        match code {
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Enter => {
+                let input = input.trim().to_owned();
+                match mode {
+                    Mode::Add(_) if !input.is_empty() => {
+                        self.user
+                            .create_todo(input)
+                            .set_status(TodoStatus::Pending)
+                            .await?;
+                    }
+                    Mode::Edit(_) if !input.is_empty() => {
+                        if let Some(todo) = self.selected() {
+                            todo.set_title(input).update().await?;
+                        }
+                    }
+                    Mode::Filter(_) => {
+                        self.title = input;
+                        self.offset = 0;
+                    }
+                    _ => return Ok(()),
+                }
+                return self.refresh().await;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(chr) => input.push(chr),
+            _ => (),
+        }
+        self.mode = mode;
+        Ok(())
+    }
+
+    /// Move the selection by the given amount, within the page.
+    fn select_next(&mut self, by: isize) {
+        if !self.todos.is_empty() {
+            let selected = self.list.selected().unwrap_or_default() as isize + by;
+            self.list.select(Some(
+                selected.clamp(0, self.todos.len() as isize - 1) as usize
+            ));
+        }
+    }
+
+    /// Draw the todos and the status line.
+    fn draw<B: Backend>(&mut self, frame: &mut Frame<B>) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.size());
+        let selected = self.list.selected();
+        let items: Vec<ListItem> = self
+            .todos
+            .iter()
+            .enumerate()
+            .map(|(idx, todo)| {
+                let title = match &self.mode {
+                    Mode::Edit(input) if Some(idx) == selected => Span::styled(
+                        format!("{input}\u{258f}"),
+                        Style::default().add_modifier(Modifier::UNDERLINED),
+                    ),
+                    _ => Span::raw(todo.title().to_owned()),
+                };
+                ListItem::new(Spans::from(vec![
+                    Span::styled(
+                        format!("{:<10}", todo.status().to_string()),
+                        Style::default().fg(status_color(todo.status())),
+                    ),
+                    title,
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(self.title()))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, chunks[0], &mut self.list);
+
+        let line = match &self.mode {
+            Mode::Normal if self.message.is_empty() => HELP.to_owned(),
+            Mode::Normal => self.message.clone(),
+            Mode::Add(input) => format!("New todo: {input}\u{258f}"),
+            Mode::Edit(_) => "Editing the title, Enter to save, Esc to cancel".to_owned(),
+            Mode::Filter(input) => format!("Title filter: {input}\u{258f}"),
+            Mode::ConfirmDelete => format!(
+                "Delete `{}`? [y/N]",
+                self.selected().map(Todo::title).unwrap_or_default()
+            ),
+        };
+        frame.render_widget(Paragraph::new(line), chunks[1]);
+    }
+
+    /// Returns the title of the todos block, with the page and the filters.
+    fn title(&self) -> String {
+        let mut title = format!(" Todos, page {} ", self.offset / PAGE_SIZE + 1);
+        if let Some(status) = &self.status {
+            title.push_str(&format!("[status: {status}] "));
+        }
+        if !self.title.is_empty() {
+            title.push_str(&format!("[title: {}] ", self.title));
+        }
+        title
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Login the user, then run the UI and restore the terminal.
+async fn run() -> OxideResult<()> {
+    let mut app = App::new(login()?);
+    // Fail before taking the terminal if the server is not reachable
+    app.refresh().await?;
+
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    app.run(&mut terminal).await
+}
+
+impl TerminalGuard {
+    /// Take the terminal (the raw mode and the alternate screen), it's restored when the guard is dropped.
+    /// The terminal is also restored before a panic message is printed, so the message is readable.
+    fn enter() -> io::Result<Self> {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            hook(info);
+        }));
+        enable_raw_mode()?;
+        // The guard restores the raw mode if entering the alternate screen fails
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leave the raw mode and the alternate screen, and show the cursor. The errors are ignored, it's best effort.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+}
+
+/// Wait for the next terminal event on a blocking thread, so the runtime is not blocked while waiting.
+async fn read_event() -> io::Result<Event> {
+    tokio::task::spawn_blocking(event::read)
+        .await
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+}
+
+/// Login the user with the profile or the environment, or with the session of the command-line client.
+fn login() -> OxideResult<User> {
+    if let Ok(profile) = env::var(PROFILE_ENV) {
+        return Client::from_profile(&profile)?.user();
+    }
+    let session = match session_path() {
+        Some(path) => Session::load(path)?,
+        None => None,
+    };
+    let client = if env::var_os(URL_ENV).is_some() || env::var_os(TOKEN_ENV).is_some() {
+        Client::from_env()?
+    } else {
+        Client::new(session.as_ref().map_or(DEFAULT_URL, Session::base_url))
+    };
+    if client.token().is_some() {
+        return client.user();
+    }
+    // The session token is never sent to another server than the session one
+    let session = session.ok_or_else(|| {
+        SDKError::missing_field(
+            "`token` you are not logged in, use `oxide-todo login` or set `OXIDE_TODO_TOKEN`",
+        )
+    })?;
+    client.resume(session)
+}
+
+/// Returns the next status of the status cycle. (pending, progress, completed, cancelled)
+/// An unknown status goes back to pending.
+fn next_status(status: &TodoStatus) -> TodoStatus {
+    match status {
+        TodoStatus::Pending => TodoStatus::Progress,
+        TodoStatus::Progress => TodoStatus::Completed,
+        TodoStatus::Completed => TodoStatus::Cancelled,
+        TodoStatus::Cancelled | TodoStatus::Unknown(_) => TodoStatus::Pending,
+    }
+}
+
+/// Returns the color of the status.
+fn status_color(status: &TodoStatus) -> Color {
+    match status {
+        TodoStatus::Pending => Color::Yellow,
+        TodoStatus::Progress => Color::Blue,
+        TodoStatus::Completed => Color::Green,
+        TodoStatus::Cancelled => Color::DarkGray,
+        TodoStatus::Unknown(_) => Color::Magenta,
+    }
+}
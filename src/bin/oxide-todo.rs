@@ -0,0 +1,398 @@
+//! The `oxide-todo` command-line client of the Oxide Todo API. (Requires the `cli` feature)
+//!
+//! The session of the logged in user is stored in the config directory, so the todo commands don't need the token.
+
+#![forbid(unsafe_code)]
+
+use clap::{Args, Parser, Subcommand};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+    tty::IsTty,
+};
+use oxide_todo_sdk::{
+    config::session_path,
+    errors::{Error, Result as OxideResult, SDKError},
+    import::map_status,
+    types::{Session, Todo, TodoOrder, TodoOrderBy, TodoStatus, User},
+    Client,
+};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+use uuid::Uuid;
+
+/// The default server url, when there is no `--url` and no session.
+const DEFAULT_URL: &str = "http://localhost:8080";
+
+/// Command-line client for the Oxide Todo API.
+#[derive(Parser)]
+#[command(name = "oxide-todo", version)]
+struct Cli {
+    /// The server url. (default: the session server, or http://localhost:8080)
+    /// The session is only used with its server, login again to use another server.
+    #[arg(long, global = true, env = "OXIDE_TODO_URL")]
+    url: Option<String>,
+    /// Print the output as JSON instead of a table.
+    #[arg(long, global = true)]
+    json: bool,
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The commands of the command-line client.
+#[derive(Subcommand)]
+enum Command {
+    /// Register a new user, and store the session.
+    Register(Credentials),
+    /// Login a user, and store the session.
+    Login(Credentials),
+    /// Revoke the session token, and remove the session.
+    Logout,
+    /// Create a new todo.
+    Add {
+        /// The todo title.
+        title: String,
+        /// The todo status.
+        #[arg(long, default_value = "pending", value_parser = parse_status)]
+        status: TodoStatus,
+    },
+    /// List the todos.
+    List {
+        /// Only the todos with this status.
+        #[arg(long, value_parser = parse_status)]
+        status: Option<TodoStatus>,
+        /// Only the todos which contain this title.
+        #[arg(long)]
+        title: Option<String>,
+        /// The order of the todos. (newer, older)
+        #[arg(long, default_value = "newer")]
+        order: TodoOrder,
+        /// The field to order the todos by. (created_at, updated_at)
+        #[arg(long, default_value = "created_at")]
+        order_by: TodoOrderBy,
+        /// The maximum amount of todos.
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// The amount of todos to skip.
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+    },
+    /// Show a todo.
+    Show {
+        /// The todo uuid.
+        uuid: Uuid,
+    },
+    /// Update the title or the status of a todo.
+    Edit {
+        /// The todo uuid.
+        uuid: Uuid,
+        /// The new title.
+        #[arg(long)]
+        title: Option<String>,
+        /// The new status.
+        #[arg(long, value_parser = parse_status)]
+        status: Option<TodoStatus>,
+    },
+    /// Mark a todo as completed.
+    Done {
+        /// The todo uuid.
+        uuid: Uuid,
+    },
+    /// Delete a todo.
+    Rm {
+        /// The todo uuid.
+        uuid: Uuid,
+    },
+    /// Delete all the todos.
+    Clear {
+        /// Don't ask for confirmation.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+/// The user credentials.
+#[derive(Args)]
+struct Credentials {
+    /// The username.
+    username: String,
+    /// The password. (default: read from the standard input)
+    #[arg(long, env = "OXIDE_TODO_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+}
+
+/// Returns the path of the session file.
+fn session_file() -> OxideResult<PathBuf> {
+    session_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory found").into())
+}
+
+/// Store the session of the user.
+fn store_session(user: &User) -> OxideResult<()> {
+    let path = session_file()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    user.session().save(path)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the command.
+async fn run(cli: Cli) -> OxideResult<()> {
+    let session = Session::load(session_file()?)?;
+    let url = cli
+        .url
+        .clone()
+        .or_else(|| session.as_ref().map(|s| s.base_url().to_owned()))
+        .unwrap_or_else(|| DEFAULT_URL.to_owned());
+    let client = Client::new(&url);
+    // The logged in user, the commands that need it will fail if there is no session.
+    // The session token is never sent to another server than the session one, login again to use another server
+    let user = || -> OxideResult<User> {
+        let session = session.clone().ok_or_else(|| -> Error {
+            SDKError::missing_field("`session` you are not logged in, use `oxide-todo login`")
+                .into()
+        })?;
+        client.resume(session)
+    };
+    match cli.command {
+        Command::Register(credentials) => {
+            let password = password(credentials.password)?;
+            let user = client.register(&credentials.username, password).await?;
+            store_session(&user)?;
+            print_message(
+                serde_json::json!({ "username": credentials.username }),
+                &format!("Registered and logged in as `{}`", credentials.username),
+                cli.json,
+            )?;
+        }
+        Command::Login(credentials) => {
+            let password = password(credentials.password)?;
+            let user = client.login(&credentials.username, password).await?;
+            store_session(&user)?;
+            print_message(
+                serde_json::json!({ "username": credentials.username }),
+                &format!("Logged in as `{}`", credentials.username),
+                cli.json,
+            )?;
+        }
+        Command::Logout => {
+            // The new token is dropped, the user needs to login again
+            let _ = user()?.revoke_token().await?;
+            let path = session_file()?;
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            print_message(
+                serde_json::json!({ "logged_out": true }),
+                "Logged out",
+                cli.json,
+            )?;
+        }
+        Command::Add { title, status } => {
+            let todo = user()?.create_todo(title).set_status(status).await?;
+            print_todos(&[todo], cli.json)?;
+        }
+        Command::List {
+            status,
+            title,
+            order,
+            order_by,
+            limit,
+            offset,
+        } => {
+            let mut todos = user()?
+                .todos()
+                .order(order)
+                .order_by(order_by)
+                .limit(limit)
+                .offset(offset);
+            if let Some(status) = status {
+                todos = todos.status(status);
+            }
+            if let Some(title) = title {
+                todos = todos.title(title);
+            }
+            print_todos(&todos.await?, cli.json)?;
+        }
+        Command::Show { uuid } => {
+            let todo = user()?.todo_by_uuid(uuid).await?;
+            print_todos(&[todo], cli.json)?;
+        }
+        Command::Edit {
+            uuid,
+            title,
+            status,
+        } => {
+            if title.is_none() && status.is_none() {
+                return Err(SDKError::missing_field("`--title` or `--status` is required").into());
+            }
+            let mut patch = user()?.update_todo(uuid);
+            if let Some(title) = title {
+                patch = patch.set_title(title);
+            }
+            if let Some(status) = status {
+                patch = patch.set_status(status);
+            }
+            print_todos(&[patch.await?], cli.json)?;
+        }
+        Command::Done { uuid } => {
+            let todo = user()?
+                .update_todo(uuid)
+                .set_status(TodoStatus::Completed)
+                .await?;
+            print_todos(&[todo], cli.json)?;
+        }
+        Command::Rm { uuid } => {
+            user()?.delete_todo(uuid).await?;
+            print_message(
+                serde_json::json!({ "deleted": uuid }),
+                &format!("Deleted `{uuid}`"),
+                cli.json,
+            )?;
+        }
+        Command::Clear { yes } => {
+            let user = user()?;
+            let deleted = yes || confirm("Delete all the todos?")?;
+            if deleted {
+                user.delete_all_todos().await?;
+            }
+            print_message(
+                serde_json::json!({ "deleted_all": deleted }),
+                if deleted {
+                    "Deleted all the todos"
+                } else {
+                    "Nothing deleted"
+                },
+                cli.json,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Print the todos as a table, or as JSON.
+fn print_todos(todos: &[Todo], json: bool) -> OxideResult<()> {
+    let mut stdout = io::stdout().lock();
+    if json {
+        serde_json::to_writer_pretty(&mut stdout, todos)?;
+        writeln!(stdout)?;
+        return Ok(());
+    }
+    let status_width = todos
+        .iter()
+        .map(|todo| todo.status().to_string().len())
+        .chain(Some("STATUS".len()))
+        .max()
+        .unwrap_or_default();
+    writeln!(stdout, "{:<36}  {:<status_width$}  TITLE", "UUID", "STATUS")?;
+    for todo in todos {
+        writeln!(
+            stdout,
+            "{:<36}  {:<status_width$}  {}",
+            todo.uuid(),
+            todo.status().to_string(),
+            todo.title()
+        )?;
+    }
+    Ok(())
+}
+
+/// Print the result of a command as a message, or as JSON.
+fn print_message(value: serde_json::Value, message: &str, json: bool) -> OxideResult<()> {
+    let mut stdout = io::stdout().lock();
+    if json {
+        serde_json::to_writer_pretty(&mut stdout, &value)?;
+    } else {
+        write!(stdout, "{message}")?;
+    }
+    writeln!(stdout)?;
+    Ok(())
+}
+
+/// Returns the password, or read it from the standard input.
+/// The password is read without echo from a terminal, and as a line otherwise.
+fn password(password: Option<String>) -> OxideResult<String> {
+    if let Some(password) = password {
+        return Ok(password);
+    }
+    let mut stderr = io::stderr();
+    write!(stderr, "Password: ")?;
+    stderr.flush()?;
+    if !io::stdin().is_tty() {
+        let mut password = String::new();
+        io::stdin().lock().read_line(&mut password)?;
+        return Ok(password.trim_end_matches(['\r', '\n']).to_owned());
+    }
+    terminal::enable_raw_mode()?;
+    let password = read_hidden();
+    // Restore the terminal before returning the error
+    terminal::disable_raw_mode()?;
+    writeln!(stderr)?;
+    password
+}
+
+/// Read a line from the terminal in raw mode, so the keys are not echoed.
+fn read_hidden() -> OxideResult<String> {
+    let mut password = String::new();
+    loop {
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            match code {
+                KeyCode::Enter => return Ok(password),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Interrupted,
+                        "password input cancelled",
+                    )
+                    .into())
+                }
+                KeyCode::Char(char) => password.push(char),
+                KeyCode::Backspace => {
+                    password.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Ask the user for confirmation.
+fn confirm(question: &str) -> OxideResult<bool> {
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Parse the todo status argument.
+fn parse_status(status: &str) -> Result<TodoStatus, String> {
+    match map_status(status) {
+        TodoStatus::Unknown(_) => Err(format!(
+            "unknown status `{status}`, expected pending, progress, completed or cancelled"
+        )),
+        status => Ok(status),
+    }
+}
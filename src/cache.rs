@@ -0,0 +1,320 @@
+//! The cache module. This module contains the in-memory read-through cache of the oxide todo client.
+//!
+//! The cache is disabled by default, you can enable it with [`Client::with_cache`]. When it's enabled,
+//! the responses of [`User::todo_by_uuid`] and the [`Todos`] requests are cached by the endpoint and the query,
+//! and the cached responses of a user are invalidated when the user create, update or delete a todo.
+//!
+//! The reads which need the server version never read the cache: [`Todo::fetch`], [`Todo::refresh`],
+//! [`Todo::update_if_unchanged`], the changes and the watch polling, and the replica sync.
+//!
+//! [`Client::with_cache`]: crate::Client::with_cache
+//! [`User::todo_by_uuid`]: crate::types::User::todo_by_uuid
+//! [`Todos`]: crate::types::Todos
+//! [`Todo::fetch`]: crate::types::Todo::fetch
+//! [`Todo::refresh`]: crate::types::Todo::refresh
+//! [`Todo::update_if_unchanged`]: crate::types::Todo::update_if_unchanged
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// The key of a cached response, the token of the user who made the request and the request key.
+///
+/// The token is part of the key, so the users who share a client never share a cached response.
+type Key = (String, String);
+
+/// A cached response.
+struct Entry {
+    /// The response of the request.
+    value: serde_json::Value,
+    /// When the response was cached.
+    inserted_at: Instant,
+}
+
+/// The cache entries and its limits.
+struct Inner {
+    /// The time to live of the entries.
+    ttl: Duration,
+    /// The maximum amount of entries.
+    capacity: usize,
+    /// The entries, by the token and the request key.
+    entries: HashMap<Key, Entry>,
+    /// The last invalidation of the users, by the token. See [`Cache::generation`].
+    generations: HashMap<String, u64>,
+    /// The last clear of the cache.
+    cleared: u64,
+    /// The counter of the invalidations.
+    counter: u64,
+}
+
+/// An in-memory read-through cache, with a time to live and a size bound.
+///
+/// The cache is cheap to clone, all the clones share the same entries.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::cache::Cache;
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let user = Client::new("http://localhost:8080")
+///         .with_cache(Cache::new(Duration::from_secs(30), 100))
+///         .login_by_token("YOUR_TOKEN");
+///     let todos = user.todos().await?; // From the server
+///     let todos = user.todos().await?; // From the cache
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Cache {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Cache {
+    /// Create a new cache, the entries will expire after `ttl` and the cache will hold `capacity` entries at most.
+    /// When the cache is full, the oldest entry will be removed.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                ttl,
+                capacity,
+                entries: HashMap::new(),
+                generations: HashMap::new(),
+                cleared: 0,
+                counter: 0,
+            })),
+        }
+    }
+
+    /// Returns the amount of the entries in the cache, including the expired ones which are not removed yet.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if the cache has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all the entries from the cache.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        // The clear is newer than all the invalidations of the users
+        inner.generations.clear();
+        inner.counter += 1;
+        inner.cleared = inner.counter;
+    }
+
+    /// Remove all the entries of the user with the given token.
+    pub fn invalidate_user(&self, token: impl AsRef<str>) {
+        let mut inner = self.lock();
+        let token = token.as_ref();
+        inner
+            .entries
+            .retain(|(entry_token, _), _| entry_token != token);
+        inner.counter += 1;
+        let counter = inner.counter;
+        inner.generations.insert(token.to_owned(), counter);
+    }
+
+    /// Returns the generation of the user entries, it changes when the user entries are invalidated.
+    ///
+    /// Take it before a request and insert the response with [`Cache::insert_if_unchanged`], so a response which
+    /// was read before a concurrent invalidation is never cached.
+    pub(crate) fn generation(&self, token: &str) -> u64 {
+        Self::generation_of(&self.lock(), token)
+    }
+
+    /// Returns the cached response of the key for the user with the given token, if it's not expired.
+    pub(crate) fn get(&self, token: &str, key: &str) -> Option<serde_json::Value> {
+        let mut inner = self.lock();
+        let ttl = inner.ttl;
+        let key = (token.to_owned(), key.to_owned());
+        match inner.entries.get(&key) {
+            Some(entry) if entry.inserted_at.elapsed() < ttl => Some(entry.value.clone()),
+            Some(_) => {
+                inner.entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Cache the response of the key for the user with the given token, without the generation check.
+    #[cfg(test)]
+    pub(crate) fn insert(&self, token: impl Into<String>, key: String, value: serde_json::Value) {
+        Self::insert_locked(&mut self.lock(), token.into(), key, value)
+    }
+
+    /// Cache the response of the key for the user with the given token, only if the user entries are not
+    /// invalidated since the given [`Cache::generation`].
+    pub(crate) fn insert_if_unchanged(
+        &self,
+        generation: u64,
+        token: String,
+        key: String,
+        value: serde_json::Value,
+    ) {
+        let mut inner = self.lock();
+        if Self::generation_of(&inner, &token) == generation {
+            Self::insert_locked(&mut inner, token, key, value)
+        }
+    }
+
+    /// Returns the generation of the user entries, the last invalidation of the user or the last clear.
+    fn generation_of(inner: &Inner, token: &str) -> u64 {
+        inner
+            .generations
+            .get(token)
+            .copied()
+            .unwrap_or_default()
+            .max(inner.cleared)
+    }
+
+    /// Cache the response of the key for the user with the given token, with the locked cache.
+    fn insert_locked(inner: &mut Inner, token: String, key: String, value: serde_json::Value) {
+        if inner.capacity == 0 {
+            return;
+        }
+        let key = (token, key);
+        let ttl = inner.ttl;
+        inner
+            .entries
+            .retain(|_, entry| entry.inserted_at.elapsed() < ttl);
+        if inner.entries.len() >= inner.capacity && !inner.entries.contains_key(&key) {
+            if let Some(oldest) = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(key, _)| key.clone())
+            {
+                inner.entries.remove(&oldest);
+            }
+        }
+        inner.entries.insert(
+            key,
+            Entry {
+                value,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Lock the cache, the cache is still usable if a thread panicked while holding the lock.
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.lock();
+        f.debug_struct("Cache")
+            .field("ttl", &inner.ttl)
+            .field("capacity", &inner.capacity)
+            .field("len", &inner.entries.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn get_returns_inserted_value() {
+        let cache = Cache::new(Duration::from_secs(60), 10);
+        cache.insert("token", "GET /todos".to_owned(), json!([1]));
+        assert_eq!(cache.get("token", "GET /todos"), Some(json!([1])));
+        assert_eq!(cache.get("token", "GET /other"), None);
+    }
+
+    #[test]
+    fn tokens_never_share_an_entry() {
+        let cache = Cache::new(Duration::from_secs(60), 10);
+        cache.insert("alice", "GET /todos".to_owned(), json!("alice"));
+        assert_eq!(cache.get("bob", "GET /todos"), None);
+        cache.insert("bob", "GET /todos".to_owned(), json!("bob"));
+        assert_eq!(cache.get("alice", "GET /todos"), Some(json!("alice")));
+        assert_eq!(cache.get("bob", "GET /todos"), Some(json!("bob")));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn invalidate_user_keeps_other_users() {
+        let cache = Cache::new(Duration::from_secs(60), 10);
+        cache.insert("alice", "GET /todos".to_owned(), json!(1));
+        cache.insert("bob", "GET /todos".to_owned(), json!(2));
+        cache.invalidate_user("alice");
+        assert_eq!(cache.get("alice", "GET /todos"), None);
+        assert_eq!(cache.get("bob", "GET /todos"), Some(json!(2)));
+    }
+
+    #[test]
+    fn expired_entries_are_removed() {
+        let cache = Cache::new(Duration::ZERO, 10);
+        cache.insert("token", "GET /todos".to_owned(), json!(1));
+        assert_eq!(cache.get("token", "GET /todos"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_never_caches() {
+        let cache = Cache::new(Duration::from_secs(60), 0);
+        cache.insert("token", "GET /todos".to_owned(), json!(1));
+        assert!(cache.is_empty());
+        assert_eq!(cache.get("token", "GET /todos"), None);
+    }
+
+    #[test]
+    fn full_cache_evicts_the_oldest_entry() {
+        let cache = Cache::new(Duration::from_secs(60), 2);
+        cache.insert("token", "first".to_owned(), json!(1));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("token", "second".to_owned(), json!(2));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("token", "third".to_owned(), json!(3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("token", "first"), None);
+        assert_eq!(cache.get("token", "second"), Some(json!(2)));
+        assert_eq!(cache.get("token", "third"), Some(json!(3)));
+    }
+
+    #[test]
+    fn responses_read_before_an_invalidation_are_not_cached() {
+        let cache = Cache::new(Duration::from_secs(60), 10);
+        let generation = cache.generation("alice");
+        let bob_generation = cache.generation("bob");
+        // A todo is changed while the response is read
+        cache.invalidate_user("alice");
+        cache.insert_if_unchanged(generation, "alice".to_owned(), "key".to_owned(), json!(1));
+        assert_eq!(cache.get("alice", "key"), None);
+        // The other users are not invalidated
+        cache.insert_if_unchanged(bob_generation, "bob".to_owned(), "key".to_owned(), json!(2));
+        assert_eq!(cache.get("bob", "key"), Some(json!(2)));
+        let generation = cache.generation("alice");
+        cache.insert_if_unchanged(generation, "alice".to_owned(), "key".to_owned(), json!(3));
+        assert_eq!(cache.get("alice", "key"), Some(json!(3)));
+        // A clear invalidates all the users
+        let generation = cache.generation("bob");
+        cache.clear();
+        cache.insert_if_unchanged(generation, "bob".to_owned(), "key".to_owned(), json!(4));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn replacing_a_key_does_not_evict() {
+        let cache = Cache::new(Duration::from_secs(60), 1);
+        cache.insert("token", "key".to_owned(), json!(1));
+        cache.insert("token", "key".to_owned(), json!(2));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("token", "key"), Some(json!(2)));
+    }
+}
@@ -1,10 +1,36 @@
-use crate::{api_helper::Endpoints, errors::Result as OxideResult, types::User};
+use crate::{
+    api_helper::{Endpoints, Timeouts},
+    cache::Cache,
+    errors::{Result as OxideResult, SDKError},
+    query::QueryStore,
+    types::{User, Workflow},
+    validation::{PasswordPolicy, TitleRules, UsernameRules},
+};
+use std::time::Duration;
 
 /// A client for the server.
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct Client {
     /// The base url of the server.
     base_url: String,
+    /// The cache of the client, `None` if the cache is disabled.
+    cache: Option<Cache>,
+    /// The timeouts of the requests.
+    timeouts: Timeouts,
+    /// The workflow of the status transitions, `None` if the transitions are not validated.
+    workflow: Option<Workflow>,
+    /// The rules of the todo titles, validated before they are sent.
+    title_rules: TitleRules,
+    /// The rules of the usernames, validated before registering or logging in.
+    username_rules: UsernameRules,
+    /// The password policy, validated before registering.
+    password_policy: PasswordPolicy,
+    /// The saved queries of the users, `None` if there is no query store.
+    queries: Option<QueryStore>,
+    /// The username of the default user, if it's known.
+    pub(crate) username: Option<String>,
+    /// The token of the default user, used by [`Client::user`].
+    pub(crate) token: Option<String>,
 }
 
 impl Client {
@@ -12,10 +38,184 @@ impl Client {
     pub fn new(base_url: impl AsRef<str>) -> Self {
         Self {
             base_url: base_url.as_ref().to_owned(),
+            cache: None,
+            timeouts: Timeouts::default(),
+            workflow: None,
+            title_rules: TitleRules::default(),
+            username_rules: UsernameRules::default(),
+            password_policy: PasswordPolicy::default(),
+            queries: None,
+            username: None,
+            token: None,
         }
     }
 
+    /// Enable the in-memory cache of the client. (disabled by default)
+    /// The users created by this client will share the cache. See the [`cache`] module.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::cache::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::new("http://localhost:8080").with_cache(Cache::new(Duration::from_secs(60), 500));
+    /// ```
+    ///
+    /// [`cache`]: crate::cache
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Returns the cache of the client, if the cache is enabled.
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    /// Set the timeout of the whole request, from connecting until the response body is read. (no timeout by default)
+    /// The users created by this client will use it.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::new("http://localhost:8080")
+    ///     .with_timeout(Duration::from_secs(30))
+    ///     .with_connect_timeout(Duration::from_secs(5));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.request = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of the connect phase of the requests. (no timeout by default)
+    /// The users created by this client will use it.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.connect = Some(timeout);
+        self
+    }
+
+    /// Validate the status transitions of the todos with the workflow. (not validated by default)
+    /// The users created by this client will use it. See [`Workflow`].
+    pub fn with_workflow(mut self, workflow: Workflow) -> Self {
+        self.workflow = Some(workflow);
+        self
+    }
+
+    /// Returns the workflow of the status transitions, if there is one.
+    pub fn workflow(&self) -> Option<&Workflow> {
+        self.workflow.as_ref()
+    }
+
+    /// Set the rules of the todo titles, validated before creating a todo or updating its title.
+    /// The users created by this client will use it. See [`TitleRules`].
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::validation::TitleRules;
+    ///
+    /// let client = Client::new("http://localhost:8080").with_title_rules(TitleRules::new().max_len(255));
+    /// ```
+    pub fn with_title_rules(mut self, title_rules: TitleRules) -> Self {
+        self.title_rules = title_rules;
+        self
+    }
+
+    /// Returns the rules of the todo titles.
+    pub fn title_rules(&self) -> &TitleRules {
+        &self.title_rules
+    }
+
+    /// Set the rules of the usernames, validated before registering. See [`UsernameRules`].
+    pub fn with_username_rules(mut self, username_rules: UsernameRules) -> Self {
+        self.username_rules = username_rules;
+        self
+    }
+
+    /// Returns the rules of the usernames.
+    pub fn username_rules(&self) -> &UsernameRules {
+        &self.username_rules
+    }
+
+    /// Set the password policy, validated before registering. See [`PasswordPolicy`].
+    pub fn with_password_policy(mut self, password_policy: PasswordPolicy) -> Self {
+        self.password_policy = password_policy;
+        self
+    }
+
+    /// Returns the password policy.
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+
+    /// Set the store of the saved queries, run them by name with [`User::run_query`].
+    /// The users created by this client will share the store. See the [`query`] module.
+    ///
+    /// [`query`]: crate::query
+    pub fn with_queries(mut self, queries: QueryStore) -> Self {
+        self.queries = Some(queries);
+        self
+    }
+
+    /// Returns the store of the saved queries, if there is one.
+    pub fn queries(&self) -> Option<&QueryStore> {
+        self.queries.as_ref()
+    }
+
+    /// Set the token of the default user, which is returned by [`Client::user`].
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Returns the token of the default user, if there is one.
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Returns the default user, logged in by the token of [`Client::with_token`] or of the profile.
+    /// Returns an error if there is no token.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").with_token("YOUR_TOKEN").user()?;
+    ///     // Now you can use the user to create todos, etc.
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn user(&self) -> OxideResult<User> {
+        let token = self
+            .token
+            .as_deref()
+            .ok_or_else(|| SDKError::missing_field("token"))?;
+        let mut user = self.login_by_token(token);
+        user.name = self.username.clone();
+        Ok(user)
+    }
+
+    /// Returns the base url of the server.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the timeout of the requests, if there is one.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeouts.request
+    }
+
+    /// Returns the connect timeout of the requests, if there is one.
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.timeouts.connect
+    }
+
     /// Login the user with username and password.
+    ///
+    /// The username and the password must not be empty, an empty credential returns [`SDKError::Validation`].
+    /// The username rules and the password policy are not validated on login, so the users registered before
+    /// the rules can still log in.
     /// ### Example
     /// ```rust |no_run
     /// use oxide_todo::Client;
@@ -34,18 +234,35 @@ impl Client {
         username: impl AsRef<str>,
         password: impl AsRef<str>,
     ) -> OxideResult<User> {
+        if username.as_ref().is_empty() {
+            return Err(SDKError::validation("username", "must not be empty").into());
+        }
+        if password.as_ref().is_empty() {
+            return Err(SDKError::validation("password", "must not be empty").into());
+        }
         Endpoints::Login {
             base_url: &self.base_url,
             username: username.as_ref(),
             password: password.as_ref(),
         }
+        .send(self.timeouts)
         .await
-        .map(|v| User {
-            base_url: self.base_url.clone(),
-            ..serde_json::from_value(v).unwrap()
+        .and_then(|v| {
+            Ok(User {
+                base_url: self.base_url.clone(),
+                cache: self.cache.clone(),
+                timeouts: self.timeouts,
+                workflow: self.workflow.clone(),
+                title_rules: self.title_rules,
+                queries: self.queries.clone(),
+                ..serde_json::from_value(v)?
+            })
         })
     }
     /// Register the user with username and password.
+    ///
+    /// The username is validated by the client [`UsernameRules`] and the password by the client [`PasswordPolicy`]
+    /// first, an invalid credential returns [`SDKError::Validation`] with the reasons.
     /// ### Example
     /// ```rust |no_run
     /// use oxide_todo::Client;
@@ -64,15 +281,25 @@ impl Client {
         username: impl AsRef<str>,
         password: impl AsRef<str>,
     ) -> OxideResult<User> {
+        self.username_rules.validate(username.as_ref())?;
+        self.password_policy.validate(password.as_ref())?;
         Endpoints::Register {
             base_url: &self.base_url,
             username: username.as_ref(),
             password: password.as_ref(),
         }
+        .send(self.timeouts)
         .await
-        .map(|v| User {
-            base_url: self.base_url.clone(),
-            ..serde_json::from_value(v).unwrap()
+        .and_then(|v| {
+            Ok(User {
+                base_url: self.base_url.clone(),
+                cache: self.cache.clone(),
+                timeouts: self.timeouts,
+                workflow: self.workflow.clone(),
+                title_rules: self.title_rules,
+                queries: self.queries.clone(),
+                ..serde_json::from_value(v)?
+            })
         })
     }
 
@@ -88,6 +315,11 @@ impl Client {
     pub fn login_by_token(&self, token: impl AsRef<str>) -> User {
         User {
             base_url: self.base_url.clone(),
+            cache: self.cache.clone(),
+            timeouts: self.timeouts,
+            workflow: self.workflow.clone(),
+            title_rules: self.title_rules,
+            queries: self.queries.clone(),
             name: None,
             token: token.as_ref().to_owned(),
         }
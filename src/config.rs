@@ -0,0 +1,389 @@
+//! The config module. This module contains the profiles of the servers and the accounts, loaded from a TOML file
+//! and the environment. (Requires the `config` feature)
+//!
+//! The config file is `OXIDE_TODO_CONFIG` if it's set, else `oxide-todo/config.toml` in the config directory.
+//! Each profile has the server url, and optionally the username, the token source and the timeouts in seconds.
+//! The token source is one of `token` (the token itself), `token_env` (an environment variable) or
+//! `token_file` (a file which contains the token).
+//! ```toml
+//! default = "local"
+//!
+//! [profiles.local]
+//! url = "http://localhost:8080"
+//! username = "awiteb"
+//! token = "YOUR_TOKEN"
+//!
+//! [profiles.production]
+//! url = "https://todo.example.com"
+//! token_env = "PRODUCTION_TODO_TOKEN"
+//! timeout = 30
+//! connect_timeout = 5
+//! ```
+//! The profiles are used as they are written, the environment never overrides them, so a token of another profile
+//! exported in the shell is never sent to the profile server. The `OXIDE_TODO_URL` and `OXIDE_TODO_TOKEN`
+//! environment variables are only read by [`Client::from_env`].
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let user = Client::from_profile("production")?.user()?;
+//!     for todo in user.todos().await? {
+//!         println!("{}", todo.title());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    errors::{Result as OxideResult, SDKError},
+    Client,
+};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The environment variable of the config file path.
+pub const CONFIG_ENV: &str = "OXIDE_TODO_CONFIG";
+/// The environment variable of the server url, read by [`Client::from_env`].
+pub const URL_ENV: &str = "OXIDE_TODO_URL";
+/// The environment variable of the user token, read by [`Client::from_env`].
+pub const TOKEN_ENV: &str = "OXIDE_TODO_TOKEN";
+
+/// The source of the profile token.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum TokenSource {
+    /// The token itself. (`token`)
+    Inline(String),
+    /// The environment variable which contains the token. (`token_env`)
+    Env(String),
+    /// The file which contains the token. (`token_file`)
+    File(PathBuf),
+}
+
+/// A profile of the config file, a server and an account.
+#[derive(Clone, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(try_from = "RawProfile")]
+pub struct Profile {
+    /// The server url.
+    pub(crate) url: String,
+    /// The username of the account.
+    pub(crate) username: Option<String>,
+    /// The source of the token, `None` if the profile has no token.
+    pub(crate) token: Option<TokenSource>,
+    /// The timeout of the requests.
+    pub(crate) timeout: Option<Duration>,
+    /// The connect timeout of the requests.
+    pub(crate) connect_timeout: Option<Duration>,
+}
+
+/// The profile as it's written in the config file.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProfile {
+    url: String,
+    username: Option<String>,
+    token: Option<String>,
+    token_env: Option<String>,
+    token_file: Option<PathBuf>,
+    timeout: Option<u64>,
+    connect_timeout: Option<u64>,
+}
+
+/// The config file, the named profiles.
+#[derive(Clone, Default, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The name of the default profile.
+    #[serde(default)]
+    pub(crate) default: Option<String>,
+    /// The profiles by name.
+    #[serde(default)]
+    pub(crate) profiles: BTreeMap<String, Profile>,
+}
+
+impl TryFrom<RawProfile> for Profile {
+    type Error = String;
+
+    fn try_from(raw: RawProfile) -> Result<Self, Self::Error> {
+        let token = match (raw.token, raw.token_env, raw.token_file) {
+            (None, None, None) => None,
+            (Some(token), None, None) => Some(TokenSource::Inline(token)),
+            (None, Some(var), None) => Some(TokenSource::Env(var)),
+            (None, None, Some(path)) => Some(TokenSource::File(path)),
+            _ => return Err("only one of `token`, `token_env` and `token_file` can be set".into()),
+        };
+        Ok(Self {
+            url: raw.url,
+            username: raw.username,
+            token,
+            timeout: raw.timeout.map(Duration::from_secs),
+            connect_timeout: raw.connect_timeout.map(Duration::from_secs),
+        })
+    }
+}
+
+impl Profile {
+    /// Returns the server url of the profile.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the username of the profile, if it's set.
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// Returns the token source of the profile, if it's set.
+    pub fn token_source(&self) -> Option<&TokenSource> {
+        self.token.as_ref()
+    }
+
+    /// Returns the timeout of the requests, if it's set.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the connect timeout of the requests, if it's set.
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Read the token from its source, `None` if the profile has no token.
+    /// Returns an error if the environment variable is not set or the file can't be read.
+    pub fn token(&self) -> OxideResult<Option<String>> {
+        Ok(match &self.token {
+            None => None,
+            Some(TokenSource::Inline(token)) => Some(token.clone()),
+            Some(TokenSource::Env(var)) => {
+                Some(env::var(var).map_err(|_| {
+                    SDKError::missing_field(format!("`{var}` environment variable"))
+                })?)
+            }
+            Some(TokenSource::File(path)) => Some(fs::read_to_string(path)?.trim().to_owned()),
+        })
+    }
+
+    /// Returns a client of the profile, with its url, its timeouts and its token.
+    /// The `OXIDE_TODO_URL` and `OXIDE_TODO_TOKEN` environment variables don't override the profile.
+    pub fn client(&self) -> OxideResult<Client> {
+        let mut client = Client::new(&self.url);
+        if let Some(timeout) = self.timeout {
+            client = client.with_timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            client = client.with_connect_timeout(timeout);
+        }
+        client.username = self.username.clone();
+        client.token = self.token()?;
+        Ok(client)
+    }
+}
+
+impl Config {
+    /// Returns the path of the config file, `OXIDE_TODO_CONFIG` if it's set, else `oxide-todo/config.toml`
+    /// in the config directory. `None` if there is no config directory.
+    pub fn path() -> Option<PathBuf> {
+        env::var_os(CONFIG_ENV)
+            .map(PathBuf::from)
+            .or_else(|| dirs::config_dir().map(|dir| dir.join("oxide-todo").join("config.toml")))
+    }
+
+    /// Load the config file of [`Config::path`], an empty config if the file doesn't exist.
+    pub fn load() -> OxideResult<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::from_file(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Load the config from the given TOML file.
+    pub fn from_file(path: impl AsRef<Path>) -> OxideResult<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse the config from TOML.
+    pub fn parse(config: &str) -> OxideResult<Self> {
+        toml::from_str(config).map_err(From::from)
+    }
+
+    /// Returns the name of the default profile, if it's set.
+    pub fn default_profile(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    /// Returns the profile with the given name, or an error if there is no such profile.
+    pub fn profile(&self, name: &str) -> OxideResult<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| SDKError::UnknownProfile(name.to_owned()).into())
+    }
+
+    /// Returns the profiles by name, sorted by name.
+    pub fn profiles(&self) -> impl Iterator<Item = (&str, &Profile)> {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+    }
+}
+
+/// Returns the path of the session file of the `oxide-todo` command-line client and the `oxide-todo-tui`
+/// terminal UI, `oxide-todo/session.json` in the config directory. `None` if there is no config directory.
+/// See [`Session`].
+///
+/// [`Session`]: crate::types::Session
+pub fn session_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("oxide-todo").join("session.json"))
+}
+
+impl Client {
+    /// Create a client from the profile of the config file. See the [`config`] module. (Requires the `config` feature)
+    ///
+    /// [`config`]: crate::config
+    pub fn from_profile(name: &str) -> OxideResult<Self> {
+        Config::load()?.profile(name)?.client()
+    }
+
+    /// Create a client from the default profile of the config file, see [`Client::from_profile`].
+    /// Returns an error if the config has no default profile. (Requires the `config` feature)
+    pub fn from_default_profile() -> OxideResult<Self> {
+        let config = Config::load()?;
+        let name = config
+            .default_profile()
+            .ok_or_else(|| SDKError::missing_field("`default` profile"))?;
+        config.profile(name)?.client()
+    }
+
+    /// Create a client from the `OXIDE_TODO_URL` and `OXIDE_TODO_TOKEN` environment variables.
+    /// Returns an error if `OXIDE_TODO_URL` is not set, the token is optional. (Requires the `config` feature)
+    ///
+    /// This is the only constructor which reads these variables, the profiles are never overridden by them.
+    pub fn from_env() -> OxideResult<Self> {
+        let url = env::var(URL_ENV)
+            .map_err(|_| SDKError::missing_field(format!("`{URL_ENV}` environment variable")))?;
+        let client = Self::new(url);
+        Ok(match env::var(TOKEN_ENV) {
+            Ok(token) => client.with_token(token),
+            Err(_) => client,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    #[test]
+    fn token_sources() {
+        let config = Config::parse(
+            r#"
+            default = "local"
+
+            [profiles.local]
+            url = "http://localhost:8080"
+            token = "secret"
+
+            [profiles.env]
+            url = "http://localhost:8080"
+            token_env = "OXIDE_TODO_CONFIG_TEST_TOKEN"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.default_profile(), Some("local"));
+        let local = config.profile("local").unwrap();
+        assert!(local.token_source() == Some(&TokenSource::Inline("secret".to_owned())));
+        assert_eq!(local.client().unwrap().token(), Some("secret"));
+        let profile = config.profile("env").unwrap();
+        assert!(profile.token().is_err());
+        env::set_var("OXIDE_TODO_CONFIG_TEST_TOKEN", "from env");
+        assert_eq!(profile.token().unwrap().as_deref(), Some("from env"));
+    }
+
+    #[test]
+    fn both_token_sources_are_rejected() {
+        assert!(Config::parse(
+            r#"
+            [profiles.local]
+            url = "http://localhost:8080"
+            token = "secret"
+            token_env = "TOKEN"
+            "#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn profile_without_token() {
+        let config = Config::parse(
+            r#"
+            [profiles.local]
+            url = "http://localhost:8080"
+            username = "awiteb"
+            "#,
+        )
+        .unwrap();
+        let profile = config.profile("local").unwrap();
+        assert!(profile.token_source().is_none());
+        assert_eq!(profile.token().unwrap(), None);
+        assert_eq!(profile.username(), Some("awiteb"));
+        let client = profile.client().unwrap();
+        assert_eq!(client.token(), None);
+        assert!(client.user().is_err());
+    }
+
+    #[test]
+    fn missing_profile() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.default_profile(), None);
+        assert!(matches!(
+            config.profile("production"),
+            Err(Error::SDKError(SDKError::UnknownProfile(name))) if name == "production"
+        ));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(Config::parse("defaults = \"local\"").is_err());
+        assert!(Config::parse(
+            r#"
+            [profiles.local]
+            url = "http://localhost:8080"
+            password = "secret"
+            "#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn timeouts_are_in_seconds() {
+        let config = Config::parse(
+            r#"
+            [profiles.local]
+            url = "http://localhost:8080"
+            timeout = 30
+            connect_timeout = 5
+            "#,
+        )
+        .unwrap();
+        let profile = config.profile("local").unwrap();
+        assert_eq!(profile.timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(profile.connect_timeout(), Some(Duration::from_secs(5)));
+        assert!(Config::parse(
+            r#"
+            [profiles.local]
+            url = "http://localhost:8080"
+            timeout = "30s"
+            "#,
+        )
+        .is_err());
+    }
+}
@@ -1,5 +1,8 @@
 //! The errors module. This module contains all the errors which can be returned by the oxide todo client.
 
+use crate::types::{Todo, TodoStatus};
+use std::fmt;
+
 /// The error message returned by the server.
 #[derive(Debug, thiserror::Error, serde::Deserialize)]
 #[error("{status}: {message}")]
@@ -10,6 +13,18 @@ pub struct ErrorMessage {
     status: u16,
 }
 
+impl ErrorMessage {
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the error code. (HTTP status code)
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 /// The error returned by the oxide todo sdk.
 pub enum SDKError {
@@ -17,6 +32,52 @@ pub enum SDKError {
     /// For example, when the user try
     #[error("Missing field: {0}")]
     MissingField(String),
+    /// Error when the uuid of the todo is not the expected one. (This is not an error from the server.)
+    /// For example, when the user try to apply a patch of a todo to another todo.
+    #[error("Uuid mismatch: expected `{expected}`, found `{found}`")]
+    UuidMismatch {
+        /// The expected uuid.
+        expected: uuid::Uuid,
+        /// The found uuid.
+        found: uuid::Uuid,
+    },
+    /// Error when the base url is not the expected one. (This is not an error from the server.)
+    /// For example, when the user try to resume a session of another server.
+    #[error("Base url mismatch: expected `{expected}`, found `{found}`")]
+    BaseUrlMismatch {
+        /// The expected base url.
+        expected: String,
+        /// The found base url.
+        found: String,
+    },
+    /// Error when the input can't be parsed. (This is not an error from the server.)
+    /// For example, when the user try to import a malformed CSV file.
+    #[error("Parse error at line {line}: {reason}")]
+    Parse {
+        /// The line of the error, starting from 1.
+        line: usize,
+        /// Why the line can't be parsed.
+        reason: String,
+    },
+    /// Error when the status transition is forbidden by the client workflow. (This is not an error from the server.)
+    /// For example, when the user try to complete a cancelled todo.
+    #[error(transparent)]
+    InvalidTransition(#[from] InvalidTransition),
+    /// Error when the profile is not in the config file. (This is not an error from the server.)
+    #[error("Unknown profile: `{0}`")]
+    UnknownProfile(String),
+    /// Error when the saved query is not in the client query store. (This is not an error from the server.)
+    #[error("Unknown query: `{0}`")]
+    UnknownQuery(String),
+    /// Error when the input is rejected by the client-side validation. (This is not an error from the server.)
+    /// For example, when the user try to create a todo with an empty title.
+    #[error("Invalid {field}: {reason}")]
+    Validation {
+        /// The invalid field.
+        field: String,
+        /// Why the field is invalid.
+        reason: String,
+    },
 }
 
 impl SDKError {
@@ -24,6 +85,112 @@ impl SDKError {
     pub fn missing_field(field: impl Into<String>) -> Self {
         Self::MissingField(field.into())
     }
+
+    /// Returns a new `SDKError::Parse` error.
+    pub fn parse(line: usize, reason: impl Into<String>) -> Self {
+        Self::Parse {
+            line,
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns a new `SDKError::Validation` error.
+    pub fn validation(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Validation {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// The invalid transition error, returned when the status transition is forbidden by the client workflow.
+/// See [`Workflow`].
+///
+/// [`Workflow`]: crate::types::Workflow
+#[derive(thiserror::Error, Clone, PartialEq, Eq)]
+#[error(
+    "Invalid transition from `{from}` to `{to}`, allowed: {}",
+    allowed_list(allowed)
+)]
+pub struct InvalidTransition {
+    /// The current status of the todo.
+    pub(crate) from: TodoStatus,
+    /// The new status of the todo.
+    pub(crate) to: TodoStatus,
+    /// The allowed statuses from the current status.
+    pub(crate) allowed: Vec<TodoStatus>,
+}
+
+impl InvalidTransition {
+    /// Returns the current status of the todo.
+    pub fn from(&self) -> &TodoStatus {
+        &self.from
+    }
+
+    /// Returns the new status of the todo.
+    pub fn to(&self) -> &TodoStatus {
+        &self.to
+    }
+
+    /// Returns the allowed statuses from the current status.
+    pub fn allowed(&self) -> &[TodoStatus] {
+        &self.allowed
+    }
+}
+
+impl fmt::Debug for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidTransition")
+            .field("from", &self.from.to_string())
+            .field("to", &self.to.to_string())
+            .field("allowed", &allowed_list(&self.allowed))
+            .finish()
+    }
+}
+
+/// Returns the allowed statuses as a list, `none` if there are no allowed statuses.
+fn allowed_list(allowed: &[TodoStatus]) -> String {
+    if allowed.is_empty() {
+        return "none".to_owned();
+    }
+    allowed
+        .iter()
+        .map(|status| format!("`{status}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The conflict error, returned when the todo has been updated on the server since it was fetched.
+/// See [`Todo::update_if_unchanged`].
+#[derive(thiserror::Error)]
+#[error("The todo `{}` has been updated on the server since it was fetched", local.uuid())]
+pub struct TodoConflict {
+    /// The local version of the todo.
+    pub(crate) local: Todo,
+    /// The server version of the todo.
+    pub(crate) server: Todo,
+}
+
+impl TodoConflict {
+    /// Returns the local version of the todo. (The version you tried to update)
+    pub fn local(&self) -> &Todo {
+        &self.local
+    }
+
+    /// Returns the server version of the todo. (The latest version)
+    pub fn server(&self) -> &Todo {
+        &self.server
+    }
+}
+
+impl fmt::Debug for TodoConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TodoConflict")
+            .field("uuid", &self.local.uuid())
+            .field("local_updated_at", &self.local.updated_at())
+            .field("server_updated_at", &self.server.updated_at())
+            .finish()
+    }
 }
 
 /// The errors coming from the oxide todo client.
@@ -38,6 +205,32 @@ pub enum Error {
     ///  The SDK error.
     #[error("SDK error: {0}")]
     SDKError(#[from] SDKError),
+    /// The IO error, when reading or writing a local file.
+    #[error("IO error: {0}")]
+    IOError(#[from] std::io::Error),
+    /// The JSON error, when serializing or deserializing a local file, or when a server response doesn't have the
+    /// expected fields. A todo status which is not known by the SDK is not an error, it's [`TodoStatus::Unknown`].
+    #[error("JSON error: {0}")]
+    JSONError(#[from] serde_json::Error),
+    /// The TOML error, when parsing the config file. (Requires the `config` feature)
+    #[cfg(feature = "config")]
+    #[error("TOML error: {0}")]
+    TOMLError(#[from] toml::de::Error),
+    /// The conflict error, the todo has been updated on the server since it was fetched.
+    #[error("Conflict: {0}")]
+    Conflict(Box<TodoConflict>),
+}
+
+impl Error {
+    /// Returns `true` if the error is a connection error or a timeout, which means the server is unreachable.
+    pub fn is_offline(&self) -> bool {
+        matches!(self, Self::ReqwestError(err) if err.is_connect() || err.is_timeout())
+    }
+
+    /// Returns `true` if the server responded with not found error.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::APIError(err) if err.status == 404)
+    }
 }
 
 /// The result type of the oxide todo client.
@@ -0,0 +1,135 @@
+//! The export module. This module contains the export of the user todos to JSON Lines, CSV and Markdown.
+//!
+//! The todos are fetched page by page with the [`Todos`] filters, and each page is written before the next one is fetched.
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//! use oxide_todo_sdk::export::ExportFormat;
+//! use oxide_todo_sdk::types::TodoStatus;
+//! use std::fs::File;
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+//!     // Backup all the todos
+//!     user.todos().limit(100).export(ExportFormat::JsonLines, File::create("todos.jsonl")?).await?;
+//!     // The pending homeworks as a checklist
+//!     user.todos()
+//!         .status(TodoStatus::Pending)
+//!         .title("Homework")
+//!         .export(ExportFormat::Markdown, File::create("homeworks.md")?)
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    errors::Result as OxideResult,
+    types::{Todo, TodoStatus, Todos},
+};
+use std::io::Write;
+
+/// The CSV header, the order of the todo fields in each CSV record.
+pub(crate) const CSV_HEADER: [&str; 5] = ["uuid", "title", "status", "created_at", "updated_at"];
+
+/// The export format.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ExportFormat {
+    /// One JSON object per line, with the todo fields. (`uuid`, `title`, `status`, `created_at`, `updated_at`)
+    JsonLines,
+    /// CSV with a header, with the todo fields. (`uuid`, `title`, `status`, `created_at`, `updated_at`)
+    Csv,
+    /// Markdown checklist, `- [x]` for the completed todos and `- [ ]` for the others, the cancelled todos are struck through.
+    Markdown,
+}
+
+impl Todos {
+    /// Export the todos with the filters to the writer, page by page starting from the offset.
+    /// The limit is used as the page size. Returns the amount of the exported todos.
+    /// See the [`export`] module.
+    ///
+    /// [`export`]: crate::export
+    pub async fn export(self, format: ExportFormat, mut writer: impl Write) -> OxideResult<usize> {
+        if format == ExportFormat::Csv {
+            writeln!(writer, "{}", CSV_HEADER.join(","))?;
+        }
+        let total = self
+            .for_each_page(|todos| {
+                todos
+                    .iter()
+                    .try_for_each(|todo| write_todo(&mut writer, format, todo))
+            })
+            .await?;
+        writer.flush()?;
+        Ok(total)
+    }
+}
+
+/// Write the todo to the writer with the given format.
+fn write_todo(writer: &mut impl Write, format: ExportFormat, todo: &Todo) -> OxideResult<()> {
+    match format {
+        ExportFormat::JsonLines => {
+            serde_json::to_writer(&mut *writer, todo)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Csv => writeln!(
+            writer,
+            "{},{},{},{},{}",
+            todo.uuid,
+            csv_field(&todo.title),
+            csv_field(&todo.status.to_string()),
+            todo.created_at,
+            todo.updated_at
+        )?,
+        ExportFormat::Markdown => {
+            // A checklist item is one line
+            let title = todo.title.replace(['\r', '\n'], " ");
+            match todo.status {
+                TodoStatus::Completed => writeln!(writer, "- [x] {title}")?,
+                TodoStatus::Cancelled => writeln!(writer, "- [ ] ~~{title}~~")?,
+                _ => writeln!(writer, "- [ ] {title}")?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Quote the CSV field if it contains a comma, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        import::{parse, ImportFormat},
+        types::test_todo,
+    };
+
+    #[test]
+    fn csv_quotes_the_title_and_the_status() {
+        let mut csv = CSV_HEADER.join(",") + "\n";
+        let todo = test_todo("Buy milk, eggs", "on hold, \"later\"", 1, 2);
+        let mut line = Vec::new();
+        write_todo(&mut line, ExportFormat::Csv, &todo).unwrap();
+        let line = String::from_utf8(line).unwrap();
+        assert_eq!(
+            line,
+            format!(
+                "{},\"Buy milk, eggs\",\"on hold, \"\"later\"\"\",1,2\n",
+                todo.uuid
+            )
+        );
+        csv.push_str(&line);
+        let drafts = parse(ImportFormat::Csv, &csv).unwrap();
+        assert_eq!(drafts[0].title(), "Buy milk, eggs");
+        assert!(drafts[0].status() == &TodoStatus::Unknown("on hold, \"later\"".to_owned()));
+    }
+}
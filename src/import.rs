@@ -0,0 +1,625 @@
+//! The import module. This module contains the import of todos from JSON, CSV, todo.txt and Markdown checklists.
+//!
+//! The input is parsed into [`TodoDraft`]s with [`parse`], then the drafts are created on the server with [`User::import`].
+//! [`parse`] fails on the first invalid line, use [`User::import_input`] to import the valid lines and get the invalid
+//! ones in [`ImportReport::invalid`]. A line with an empty title is invalid (e.g. `- [ ] ` or a todo.txt date only).
+//! The JSON and CSV formats are the same as the [`export`] formats, so you can import an exported backup.
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//! use oxide_todo_sdk::import::{parse, ImportFormat};
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+//!     let drafts = parse(ImportFormat::TodoTxt, &std::fs::read_to_string("todo.txt")?)?;
+//!     let report = user
+//!         .import(drafts)
+//!         .dedupe_by_title(true)
+//!         .on_progress(|done, total| println!("{done}/{total}"))
+//!         .await?;
+//!     for (draft, err) in report.failed() {
+//!         eprintln!("Failed to import `{}`: {err}", draft.title());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`User::import`]: crate::types::User::import
+//! [`User::import_input`]: crate::types::User::import_input
+//! [`export`]: crate::export
+
+use crate::{
+    errors::{Error, Result as OxideResult, SDKError},
+    export::CSV_HEADER,
+    types::{Todo, TodoStatus, User, PAGE_SIZE},
+};
+use std::{
+    collections::HashSet,
+    future::{Future, IntoFuture},
+    mem,
+    pin::Pin,
+};
+
+/// The import format.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum ImportFormat {
+    /// A JSON array of todos, or one JSON todo per line. Each todo needs a `title`, and can have a `status`.
+    Json,
+    /// CSV with a header, which needs a `title` column, and can have a `status` column.
+    Csv,
+    /// [todo.txt](https://github.com/todotxt/todo.txt) lines, the lines starting with `x ` are completed.
+    TodoTxt,
+    /// Markdown checklist, `- [x]` is completed, `- [ ]` is pending and `- [-]` or `- [ ] ~~title~~` is cancelled.
+    /// The lines that are not checklist items are ignored.
+    Markdown,
+}
+
+/// A todo to import, which is not created on the server yet.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct TodoDraft {
+    /// The todo title.
+    pub(crate) title: String,
+    /// The todo status.
+    pub(crate) status: TodoStatus,
+}
+
+/// The JSON todo, the other fields are ignored.
+#[derive(serde::Deserialize)]
+struct JsonDraft {
+    /// The todo title.
+    title: String,
+    /// The todo status.
+    #[serde(default)]
+    status: Option<String>,
+}
+
+/// The todos importer, it's created by [`User::import`]. await it to import the todos.
+///
+/// [`User::import`]: crate::types::User::import
+#[must_use]
+pub struct Importer {
+    /// The user to create the todos for.
+    user: User,
+    /// The todos to create.
+    drafts: Vec<TodoDraft>,
+    /// Skip the drafts with a title that already exists.
+    dedupe_by_title: bool,
+    /// Called after each draft with the amount of the processed drafts and the total.
+    progress: Option<Box<dyn FnMut(usize, usize) + Send>>,
+    /// The invalid lines of the input, reported in [`ImportReport::invalid`].
+    invalid: Vec<Error>,
+}
+
+/// The report of an import.
+#[derive(Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ImportReport {
+    /// The created todos.
+    pub(crate) created: Vec<Todo>,
+    /// The skipped drafts, because their title already exists.
+    pub(crate) skipped: Vec<TodoDraft>,
+    /// The drafts that failed to be created, with the error.
+    pub(crate) failed: Vec<(TodoDraft, Error)>,
+    /// The invalid lines of the input, which are not imported.
+    pub(crate) invalid: Vec<Error>,
+}
+
+impl TodoDraft {
+    /// Create a new todo draft.
+    pub fn new(title: impl Into<String>, status: TodoStatus) -> Self {
+        Self {
+            title: title.into(),
+            status,
+        }
+    }
+
+    /// Returns the title of the todo.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the status of the todo.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status
+    }
+}
+
+impl ImportReport {
+    /// Returns the created todos.
+    pub fn created(&self) -> &[Todo] {
+        &self.created
+    }
+
+    /// Returns the skipped drafts, because their title already exists.
+    pub fn skipped(&self) -> &[TodoDraft] {
+        &self.skipped
+    }
+
+    /// Returns the drafts that failed to be created, with the error.
+    pub fn failed(&self) -> &[(TodoDraft, Error)] {
+        &self.failed
+    }
+
+    /// Returns the errors of the invalid lines of the input, which are not imported. Each error is a
+    /// [`SDKError::Parse`] with its line. Always empty for the drafts of [`User::import`].
+    ///
+    /// [`User::import`]: crate::types::User::import
+    pub fn invalid(&self) -> &[Error] {
+        &self.invalid
+    }
+}
+
+impl Importer {
+    /// Skip the drafts with a title that already exists on the server or earlier in the import. (default: `false`)
+    pub fn dedupe_by_title(mut self, dedupe_by_title: bool) -> Self {
+        self.dedupe_by_title = dedupe_by_title;
+        self
+    }
+
+    /// Set the progress callback, it's called after each draft with the amount of the processed drafts and the total.
+    pub fn on_progress(mut self, progress: impl FnMut(usize, usize) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Create the drafts on the server, one by one. A draft that fails to be created doesn't stop the import,
+    /// it's added to [`ImportReport::failed`].
+    pub async fn run(mut self) -> OxideResult<ImportReport> {
+        let mut titles = if self.dedupe_by_title {
+            self.user
+                .todos()
+                .limit(PAGE_SIZE)
+                .all()
+                .await?
+                .into_iter()
+                .map(|todo| todo.title)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let mut report = ImportReport {
+            invalid: mem::take(&mut self.invalid),
+            ..ImportReport::default()
+        };
+        let total = self.drafts.len();
+        for (idx, draft) in mem::take(&mut self.drafts).into_iter().enumerate() {
+            if self.dedupe_by_title && !titles.insert(draft.title.clone()) {
+                report.skipped.push(draft);
+            } else {
+                match self
+                    .user
+                    .create_todo(&draft.title)
+                    .set_status(draft.status.clone())
+                    .await
+                {
+                    Ok(todo) => report.created.push(todo),
+                    Err(err) => report.failed.push((draft, err)),
+                }
+            }
+            if let Some(progress) = &mut self.progress {
+                progress(idx + 1, total);
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl IntoFuture for Importer {
+    type Output = OxideResult<ImportReport>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.run())
+    }
+}
+
+impl User {
+    /// Returns an importer of the drafts, await it to create them on the server. See the [`import`] module.
+    ///
+    /// [`import`]: crate::import
+    pub fn import(&self, drafts: Vec<TodoDraft>) -> Importer {
+        Importer {
+            user: self.clone(),
+            drafts,
+            dedupe_by_title: false,
+            progress: None,
+            invalid: Vec::new(),
+        }
+    }
+
+    /// Parse the input with [`parse_lenient`] and returns an importer of the valid drafts, await it to create them
+    /// on the server. The invalid lines are reported in [`ImportReport::invalid`].
+    /// Returns an error if the whole input is invalid, e.g. a malformed JSON array or a CSV without a header.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    /// use oxide_todo_sdk::import::ImportFormat;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let input = std::fs::read_to_string("TODO.md")?;
+    ///     let report = user.import_input(ImportFormat::Markdown, &input)?.await?;
+    ///     for err in report.invalid() {
+    ///         eprintln!("Skipped: {err}");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn import_input(&self, format: ImportFormat, input: &str) -> OxideResult<Importer> {
+        let (drafts, invalid) = parse_lenient(format, input)?;
+        Ok(Importer {
+            invalid,
+            ..self.import(drafts)
+        })
+    }
+}
+
+/// Parse the input into todo drafts, with the given format. Returns the error of the first invalid line.
+pub fn parse(format: ImportFormat, input: &str) -> OxideResult<Vec<TodoDraft>> {
+    parse_lines(format, input)?.into_iter().collect()
+}
+
+/// Parse the input into todo drafts, with the given format, and returns the drafts of the valid lines and
+/// the errors of the invalid lines. Returns an error if the whole input is invalid.
+pub fn parse_lenient(
+    format: ImportFormat,
+    input: &str,
+) -> OxideResult<(Vec<TodoDraft>, Vec<Error>)> {
+    let (mut drafts, mut invalid) = (Vec::new(), Vec::new());
+    for line in parse_lines(format, input)? {
+        match line {
+            Ok(draft) => drafts.push(draft),
+            Err(err) => invalid.push(err),
+        }
+    }
+    Ok((drafts, invalid))
+}
+
+/// Parse the input into the result of each line, an error if the whole input is invalid.
+fn parse_lines(format: ImportFormat, input: &str) -> OxideResult<Vec<OxideResult<TodoDraft>>> {
+    match format {
+        ImportFormat::Json => parse_json(input),
+        ImportFormat::Csv => parse_csv(input),
+        ImportFormat::TodoTxt => Ok(parse_todo_txt(input)),
+        ImportFormat::Markdown => Ok(parse_markdown(input)),
+    }
+}
+
+/// Map a status name to the todo status, it accepts the status names and their common aliases.
+/// For example `done` is [`TodoStatus::Completed`] and `in progress` is [`TodoStatus::Progress`].
+/// The other names are [`TodoStatus::Unknown`], so the exported todos with an unknown status are imported as they are.
+pub fn map_status(status: &str) -> TodoStatus {
+    let status = status.trim();
+    match status.to_lowercase().replace(['-', ' '], "_").as_str() {
+        "completed" | "complete" | "done" | "x" => TodoStatus::Completed,
+        "progress" | "in_progress" | "doing" | "started" => TodoStatus::Progress,
+        "pending" | "todo" | "open" | "" => TodoStatus::Pending,
+        "cancelled" | "canceled" => TodoStatus::Cancelled,
+        _ => TodoStatus::from_name(status),
+    }
+}
+
+/// Returns the todo draft, or an error if the title is empty.
+fn draft(line: usize, title: &str, status: Option<&str>) -> OxideResult<TodoDraft> {
+    let status = status.map_or(TodoStatus::Pending, map_status);
+    titled_draft(line, title, status)
+}
+
+/// Returns the todo draft with the status, or an error if the title is empty.
+fn titled_draft(line: usize, title: &str, status: TodoStatus) -> OxideResult<TodoDraft> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(SDKError::parse(line, "empty title").into());
+    }
+    Ok(TodoDraft::new(title, status))
+}
+
+/// Parse a JSON array of todos, or JSON Lines. The line of an array item is its position in the array.
+fn parse_json(input: &str) -> OxideResult<Vec<OxideResult<TodoDraft>>> {
+    let json_draft = |line: usize, value: &str| {
+        serde_json::from_str::<JsonDraft>(value)
+            .map_err(|err| SDKError::parse(line, err.to_string()).into())
+            .and_then(|d| draft(line, &d.title, d.status.as_deref()))
+    };
+    if input.trim_start().starts_with('[') {
+        Ok(serde_json::from_str::<Vec<serde_json::Value>>(input)?
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| json_draft(idx + 1, &value.to_string()))
+            .collect())
+    } else {
+        Ok(input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| json_draft(idx + 1, line))
+            .collect())
+    }
+}
+
+/// Parse CSV with a header, the quoted fields can contain commas, quotes and line breaks.
+fn parse_csv(input: &str) -> OxideResult<Vec<OxideResult<TodoDraft>>> {
+    let mut records = csv_records(input)?.into_iter();
+    let (_, header) = records
+        .next()
+        .ok_or_else(|| SDKError::parse(1, "missing CSV header"))?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|c| c.trim().eq_ignore_ascii_case(name))
+    };
+    let title =
+        column(CSV_HEADER[1]).ok_or_else(|| SDKError::parse(1, "missing `title` column"))?;
+    let status = column(CSV_HEADER[2]);
+    Ok(records
+        .map(|(line, record)| {
+            let field = |idx: usize| record.get(idx).map(String::as_str);
+            draft(
+                line,
+                field(title).unwrap_or_default(),
+                status.and_then(field),
+            )
+        })
+        .collect())
+}
+
+/// Split the CSV input into records, with the line where each record starts. The empty lines are skipped.
+fn csv_records(input: &str) -> OxideResult<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let (mut line, mut record_line) = (1, 1);
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => record.push(mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => (),
+            '\n' if !in_quotes => {
+                record.push(mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push((record_line, mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if in_quotes {
+        return Err(SDKError::parse(record_line, "unterminated quoted field").into());
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+/// Parse todo.txt lines, the priority and the dates are removed from the title, the projects and contexts are kept.
+fn parse_todo_txt(input: &str) -> Vec<OxideResult<TodoDraft>> {
+    let is_date = |word: &str| {
+        word.len() == 10
+            && word.char_indices().all(|(idx, c)| match idx {
+                4 | 7 => c == '-',
+                _ => c.is_ascii_digit(),
+            })
+    };
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            let mut rest = line.trim();
+            let completed = match rest.strip_prefix("x ") {
+                Some(after) => {
+                    rest = after.trim_start();
+                    true
+                }
+                None => false,
+            };
+            // The priority, `(A)` to `(Z)`
+            let bytes = rest.as_bytes();
+            if bytes.len() > 3
+                && bytes[0] == b'('
+                && bytes[1].is_ascii_uppercase()
+                && bytes[2] == b')'
+                && bytes[3] == b' '
+            {
+                rest = rest[4..].trim_start();
+            }
+            // The completion date and the creation date, a line of dates only has an empty title
+            for _ in 0..2 {
+                let (word, after) = rest.split_once(' ').unwrap_or((rest, ""));
+                if !is_date(word) {
+                    break;
+                }
+                rest = after.trim_start();
+            }
+            let status = if completed { "completed" } else { "pending" };
+            draft(idx + 1, rest, Some(status))
+        })
+        .collect()
+}
+
+/// Parse the Markdown checklist items, the other lines are ignored.
+fn parse_markdown(input: &str) -> Vec<OxideResult<TodoDraft>> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let item = line.trim_start();
+            let item = ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|bullet| item.strip_prefix(bullet))?
+                .trim_start();
+            let (status, title) = [
+                ("[ ]", TodoStatus::Pending),
+                ("[x]", TodoStatus::Completed),
+                ("[X]", TodoStatus::Completed),
+                ("[-]", TodoStatus::Cancelled),
+            ]
+            .into_iter()
+            .find_map(|(checkbox, status)| Some((status, item.strip_prefix(checkbox)?.trim())))?;
+            let (status, title) = match title.strip_prefix("~~").and_then(|t| t.strip_suffix("~~"))
+            {
+                Some(title) if status == TodoStatus::Pending => (TodoStatus::Cancelled, title),
+                _ => (status, title),
+            };
+            Some(titled_draft(idx + 1, title, status))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drafts(format: ImportFormat, input: &str) -> Vec<(String, TodoStatus)> {
+        parse(format, input)
+            .unwrap()
+            .into_iter()
+            .map(|draft| (draft.title, draft.status))
+            .collect()
+    }
+
+    fn invalid_lines(format: ImportFormat, input: &str) -> Vec<usize> {
+        parse_lenient(format, input)
+            .unwrap()
+            .1
+            .into_iter()
+            .map(|err| match err {
+                Error::SDKError(SDKError::Parse { line, .. }) => line,
+                err => panic!("unexpected error: {err}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn map_status_aliases() {
+        assert!(map_status("Done") == TodoStatus::Completed);
+        assert!(map_status("in progress") == TodoStatus::Progress);
+        assert!(map_status("in-progress") == TodoStatus::Progress);
+        assert!(map_status("") == TodoStatus::Pending);
+        assert!(map_status("canceled") == TodoStatus::Cancelled);
+        assert!(map_status(" archived ") == TodoStatus::Unknown("archived".to_owned()));
+    }
+
+    #[test]
+    fn json_array_and_lines() {
+        let expected = vec![
+            ("Buy milk".to_owned(), TodoStatus::Pending),
+            ("Homework".to_owned(), TodoStatus::Completed),
+        ];
+        let array = r#"[{"title": "Buy milk"}, {"title": "Homework", "status": "done", "uuid": "ignored"}]"#;
+        assert!(drafts(ImportFormat::Json, array) == expected);
+        let lines =
+            "{\"title\": \"Buy milk\"}\n\n{\"title\": \"Homework\", \"status\": \"completed\"}\n";
+        assert!(drafts(ImportFormat::Json, lines) == expected);
+        assert_eq!(
+            invalid_lines(
+                ImportFormat::Json,
+                "{\"title\": \" \"}\n{\"status\": \"done\"}"
+            ),
+            [1, 2]
+        );
+        assert!(parse(ImportFormat::Json, "[{").is_err());
+    }
+
+    #[test]
+    fn csv_quoted_fields() {
+        let input = "uuid,title,status\r\n1,\"Buy milk, eggs\",pending\n2,\"Say \"\"hi\"\"\nto all\",done\n\n";
+        assert!(
+            drafts(ImportFormat::Csv, input)
+                == [
+                    ("Buy milk, eggs".to_owned(), TodoStatus::Pending),
+                    ("Say \"hi\"\nto all".to_owned(), TodoStatus::Completed),
+                ]
+        );
+    }
+
+    #[test]
+    fn csv_errors() {
+        assert!(parse(ImportFormat::Csv, "").is_err());
+        assert!(parse(ImportFormat::Csv, "uuid,status\n1,done").is_err());
+        assert!(parse(ImportFormat::Csv, "title\n\"unterminated").is_err());
+        // The quoted title spans the lines 2 and 3, so the record with the empty title is at line 4
+        assert_eq!(
+            invalid_lines(
+                ImportFormat::Csv,
+                "title,status\n\"a\nb\",done\n,pending\nc,archived\n,done"
+            ),
+            [4, 6]
+        );
+    }
+
+    #[test]
+    fn todo_txt_priority_and_dates() {
+        let input = "(A) 2023-01-02 Call mom +family @phone\nx 2023-01-03 2023-01-01 Pay rent\nx (B) Water plants\n(a) lowercase is not a priority";
+        assert!(
+            drafts(ImportFormat::TodoTxt, input)
+                == [
+                    ("Call mom +family @phone".to_owned(), TodoStatus::Pending),
+                    ("Pay rent".to_owned(), TodoStatus::Completed),
+                    ("Water plants".to_owned(), TodoStatus::Completed),
+                    (
+                        "(a) lowercase is not a priority".to_owned(),
+                        TodoStatus::Pending
+                    ),
+                ]
+        );
+    }
+
+    #[test]
+    fn todo_txt_date_only_line_is_invalid() {
+        let input = "2023-01-01\nx 2023-01-02 2023-01-01\n(A) 2023-01-01 \nReal todo";
+        assert_eq!(invalid_lines(ImportFormat::TodoTxt, input), [1, 2, 3]);
+        assert!(parse(ImportFormat::TodoTxt, input).is_err());
+        let (drafts, _) = parse_lenient(ImportFormat::TodoTxt, input).unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].title(), "Real todo");
+    }
+
+    #[test]
+    fn markdown_checklist() {
+        let input = "# Todos\n- [ ] Buy milk\n* [x] Homework\n+ [X] Laundry\n  - [-] Gym\n- [ ] ~~Party~~\n- not an item\n";
+        assert!(
+            drafts(ImportFormat::Markdown, input)
+                == [
+                    ("Buy milk".to_owned(), TodoStatus::Pending),
+                    ("Homework".to_owned(), TodoStatus::Completed),
+                    ("Laundry".to_owned(), TodoStatus::Completed),
+                    ("Gym".to_owned(), TodoStatus::Cancelled),
+                    ("Party".to_owned(), TodoStatus::Cancelled),
+                ]
+        );
+    }
+
+    #[test]
+    fn markdown_empty_titles_are_invalid() {
+        let input = "- [-] \n- [ ] ~~~~\n- [x]\n- [ ] Buy milk";
+        assert_eq!(invalid_lines(ImportFormat::Markdown, input), [1, 2, 3]);
+        assert!(parse(ImportFormat::Markdown, input).is_err());
+    }
+}
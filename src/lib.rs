@@ -5,6 +5,17 @@
 mod api_helper;
 mod client;
 
+pub mod backup;
+pub mod cache;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod errors;
+pub mod export;
+pub mod import;
+pub mod query;
+pub mod search;
+#[cfg(feature = "sync")]
+pub mod sync;
 pub mod types;
+pub mod validation;
 pub use client::*;
@@ -0,0 +1,446 @@
+//! The query module. This module contains the saved queries, named presets of the todos queries.
+//!
+//! A [`SavedQuery`] captures the parameters of a [`Todos`] query (the limit, the offset, the order and the server
+//! filters), it can be written as a URL query string or as JSON. The saved queries are stored by name in a
+//! [`QueryStore`], set it with [`Client::with_queries`] and run a query by its name with [`User::run_query`].
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::query::{QueryStore, SavedQuery};
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let store = QueryStore::open("queries.json")?;
+//!     store.insert("open", "statuses=pending,progress&order_by=updated_at&limit=20".parse::<SavedQuery>()?)?;
+//!     let user = Client::new("http://localhost:8080")
+//!         .with_queries(store)
+//!         .login_by_token("YOUR_TOKEN");
+//!     for todo in user.run_query("open").await? {
+//!         println!("{}", todo.title());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`Todos`]: crate::types::Todos
+//! [`Client::with_queries`]: crate::Client::with_queries
+//! [`User::run_query`]: crate::types::User::run_query
+
+use crate::{
+    errors::{Error, Result as OxideResult, SDKError},
+    types::{Todo, TodoStatus, Todos, TodosQuery, User},
+};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// A saved todos query, a named preset of the [`Todos`] parameters.
+///
+/// It's (de)serialized as a JSON object with [`SavedQuery::to_json`] and [`SavedQuery::from_json`] (or serde),
+/// and as a URL query string with [`fmt::Display`] and [`FromStr`], e.g. `status=pending&title=Homework&limit=20`.
+/// The parameters of the query string are `limit`, `offset`, `order` (`newer` or `older`), `order_by`
+/// (`created_at` or `updated_at`), `status`, `statuses` (comma separated) and `title`, all of them are optional.
+///
+/// The query string is the canonical form, for the users and the command line. The JSON form is the serde form of
+/// [`TodosQuery`], which writes `order_by` with the server spelling (`createdat` or `updatedat`). The query string
+/// accepts both spellings, so a query can be moved from the JSON form to the query string and back.
+///
+/// A query string can't have both `status` and `statuses`. A query with both (e.g. from JSON) runs with `statuses`,
+/// which replaces the status like [`Todos::statuses`], so the status is not written to the query string.
+///
+/// The local filter of [`Todos::filter`] is not part of the query, because it can't be serialized.
+///
+/// [`Todos`]: crate::types::Todos
+/// [`Todos::filter`]: crate::types::Todos::filter
+/// [`Todos::statuses`]: crate::types::Todos::statuses
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(transparent)]
+pub struct SavedQuery {
+    /// The snapshot of the todos query.
+    query: TodosQuery,
+}
+
+/// The named saved queries, in memory or persisted to a JSON file.
+///
+/// The store is cheap to clone, all the clones share the same queries.
+#[derive(Clone)]
+pub struct QueryStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// The saved queries and the file of the store.
+#[derive(Default)]
+struct Inner {
+    /// The file of the store, `None` if the store is in memory.
+    path: Option<PathBuf>,
+    /// The saved queries, by name.
+    queries: BTreeMap<String, SavedQuery>,
+}
+
+impl SavedQuery {
+    /// Capture the parameters of the todos query.
+    pub fn new(todos: &Todos) -> Self {
+        Self {
+            query: todos.query(),
+        }
+    }
+
+    /// Returns the snapshot of the todos query.
+    pub fn query(&self) -> &TodosQuery {
+        &self.query
+    }
+
+    /// Serialize the query to a JSON object.
+    pub fn to_json(&self) -> OxideResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize the query from a JSON object, the missing parameters are the defaults of [`Todos`].
+    ///
+    /// [`Todos`]: crate::types::Todos
+    pub fn from_json(json: &str) -> OxideResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+impl From<TodosQuery> for SavedQuery {
+    fn from(query: TodosQuery) -> Self {
+        Self { query }
+    }
+}
+
+impl fmt::Display for SavedQuery {
+    /// Write the query as a URL query string, without the leading `?`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let query = &self.query;
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        serializer
+            .append_pair("limit", &query.limit().to_string())
+            .append_pair("offset", &query.offset().to_string())
+            .append_pair("order", &query.order().to_string())
+            .append_pair("order_by", &query.order_by().to_string());
+        if let Some(status) = query.status().filter(|_| query.statuses().is_empty()) {
+            serializer.append_pair("status", status.as_str());
+        }
+        if !query.statuses().is_empty() {
+            let statuses: Vec<&str> = query.statuses().iter().map(TodoStatus::as_str).collect();
+            serializer.append_pair("statuses", &statuses.join(","));
+        }
+        if let Some(title) = query.title() {
+            serializer.append_pair("title", title);
+        }
+        f.write_str(&serializer.finish())
+    }
+}
+
+impl FromStr for SavedQuery {
+    type Err = Error;
+
+    /// Parse the query from a URL query string, with or without the leading `?`.
+    /// Returns [`SDKError::Validation`] if a parameter is unknown, its value is invalid,
+    /// or both `status` and `statuses` are set.
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut parsed = TodosQuery::default();
+        for (key, value) in form_urlencoded::parse(query.trim_start_matches('?').as_bytes()) {
+            match key.as_ref() {
+                "limit" => parsed.limit = parse_number(&key, &value)?,
+                "offset" => parsed.offset = parse_number(&key, &value)?,
+                "order" => parsed.order = value.parse()?,
+                "order_by" => parsed.order_by = value.parse()?,
+                "status" => parsed.status = Some(TodoStatus::from_name(&value)),
+                "statuses" => {
+                    parsed.statuses = value
+                        .split(',')
+                        .filter(|status| !status.is_empty())
+                        .map(TodoStatus::from_name)
+                        .collect()
+                }
+                "title" => parsed.title = Some(value.into_owned()),
+                _ => {
+                    return Err(
+                        SDKError::validation("query", format!("unknown parameter `{key}`")).into(),
+                    )
+                }
+            }
+        }
+        if parsed.status.is_some() && !parsed.statuses.is_empty() {
+            return Err(SDKError::validation(
+                "query",
+                "`status` and `statuses` can't be combined, use `statuses` only",
+            )
+            .into());
+        }
+        Ok(Self { query: parsed })
+    }
+}
+
+impl QueryStore {
+    /// Create an empty store in memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the store of the JSON file, the file is created on the first change if it doesn't exist.
+    /// The changes of the store are written to the file.
+    pub fn open(path: impl AsRef<Path>) -> OxideResult<Self> {
+        let path = path.as_ref().to_owned();
+        let queries = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Inner {
+                path: Some(path),
+                queries,
+            })),
+        })
+    }
+
+    /// Save the query by name, it replaces the query with the same name. Returns the replaced query.
+    pub fn insert(
+        &self,
+        name: impl Into<String>,
+        query: SavedQuery,
+    ) -> OxideResult<Option<SavedQuery>> {
+        let mut inner = self.lock();
+        let old = inner.queries.insert(name.into(), query);
+        inner.persist()?;
+        Ok(old)
+    }
+
+    /// Remove the query by name, returns the removed query.
+    pub fn remove(&self, name: &str) -> OxideResult<Option<SavedQuery>> {
+        let mut inner = self.lock();
+        let old = inner.queries.remove(name);
+        if old.is_some() {
+            inner.persist()?;
+        }
+        Ok(old)
+    }
+
+    /// Returns the query by name, if there is one.
+    pub fn get(&self, name: &str) -> Option<SavedQuery> {
+        self.lock().queries.get(name).cloned()
+    }
+
+    /// Returns the names of the saved queries, sorted.
+    pub fn names(&self) -> Vec<String> {
+        self.lock().queries.keys().cloned().collect()
+    }
+
+    /// Returns the amount of the saved queries.
+    pub fn len(&self) -> usize {
+        self.lock().queries.len()
+    }
+
+    /// Returns `true` if there are no saved queries.
+    pub fn is_empty(&self) -> bool {
+        self.lock().queries.is_empty()
+    }
+
+    /// Lock the store, a poisoned lock is recovered since the queries are always consistent.
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for QueryStore {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl fmt::Debug for QueryStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.lock();
+        f.debug_struct("QueryStore")
+            .field("path", &inner.path)
+            .field("queries", &inner.queries)
+            .finish()
+    }
+}
+
+impl Inner {
+    /// Write the queries to the file of the store, if there is one.
+    ///
+    /// The queries are written to a temporary file of this process first, then it's renamed to the store file,
+    /// so a crash or another writer never leaves a partially written store.
+    fn persist(&self) -> OxideResult<()> {
+        if let Some(path) = &self.path {
+            let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+            fs::write(&tmp_path, serde_json::to_string_pretty(&self.queries)?)?;
+            if let Err(err) = fs::rename(&tmp_path, path) {
+                let _ = fs::remove_file(&tmp_path);
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl User {
+    /// Run the saved query of the client [`QueryStore`] by its name, and returns the todos.
+    /// Returns [`SDKError::UnknownQuery`] if there is no query with the name, or the client has no store.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::query::{QueryStore, SavedQuery};
+    /// use oxide_todo_sdk::types::TodoStatus;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let store = QueryStore::new();
+    ///     let user = Client::new("http://localhost:8080")
+    ///         .with_queries(store.clone())
+    ///         .login_by_token("YOUR_TOKEN");
+    ///     store.insert("homeworks", SavedQuery::new(&user.todos().status(TodoStatus::Pending).title("Homework")))?;
+    ///     let homeworks = user.run_query("homeworks").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn run_query(&self, name: &str) -> OxideResult<Vec<Todo>> {
+        let query = self
+            .queries
+            .as_ref()
+            .and_then(|store| store.get(name))
+            .ok_or_else(|| SDKError::UnknownQuery(name.to_owned()))?;
+        self.attach_todos(query.query).await
+    }
+}
+
+/// Parse the number value of the parameter.
+fn parse_number(key: &str, value: &str) -> OxideResult<usize> {
+    value
+        .parse()
+        .map_err(|_| invalid_value(key, value, "a number"))
+}
+
+/// Returns the validation error of an invalid parameter value.
+fn invalid_value(key: &str, value: &str, expected: &str) -> Error {
+    SDKError::validation(key, format!("expected {expected}, found `{value}`")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(query: &str) -> SavedQuery {
+        query.parse().unwrap()
+    }
+
+    #[test]
+    fn query_string_round_trip() {
+        let query = parse(
+            "status=pending&title=Home+work%26co&limit=20&offset=5&order=older&order_by=updated_at",
+        );
+        let string = query.to_string();
+        assert_eq!(
+            string,
+            "limit=20&offset=5&order=older&order_by=updated_at&status=pending&title=Home+work%26co"
+        );
+        assert_eq!(parse(&string).to_string(), string);
+        assert_eq!(query.query().title(), Some("Home work&co"));
+    }
+
+    #[test]
+    fn statuses_round_trip() {
+        let query = parse("?statuses=pending,progress,archived");
+        assert!(
+            query.query().statuses()
+                == [
+                    TodoStatus::Pending,
+                    TodoStatus::Progress,
+                    TodoStatus::Unknown("archived".to_owned())
+                ]
+        );
+        assert!(query.query().status().is_none());
+        assert_eq!(parse(&query.to_string()).to_string(), query.to_string());
+    }
+
+    #[test]
+    fn missing_parameters_are_the_defaults() {
+        let query = parse("");
+        assert_eq!(
+            query.to_string(),
+            "limit=10&offset=0&order=newer&order_by=created_at"
+        );
+    }
+
+    #[test]
+    fn status_and_statuses_are_rejected() {
+        assert!("status=pending&statuses=pending,progress"
+            .parse::<SavedQuery>()
+            .is_err());
+    }
+
+    #[test]
+    fn statuses_replace_the_status_of_a_json_query() {
+        let query = SavedQuery::from_json(
+            r#"{"status": "pending", "statuses": ["completed", "cancelled"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            query.to_string(),
+            "limit=10&offset=0&order=newer&order_by=created_at&statuses=completed%2Ccancelled"
+        );
+        assert!(parse(&query.to_string()).query().status().is_none());
+    }
+
+    #[test]
+    fn json_and_query_string_round_trip() {
+        let query = parse("order_by=updated_at&order=older&title=Homework");
+        let json = query.to_json().unwrap();
+        assert!(json.contains(r#""order_by":"updatedat""#));
+        let from_json = SavedQuery::from_json(&json).unwrap();
+        assert_eq!(from_json.to_string(), query.to_string());
+        // The serde spelling is accepted by the query string too
+        let serde_spelling = parse("order_by=updatedat&order=older&title=Homework");
+        assert_eq!(serde_spelling.to_string(), query.to_string());
+        assert_eq!(serde_spelling.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        for query in ["limit=ten", "order=random", "order_by=title", "color=red"] {
+            match query.parse::<SavedQuery>() {
+                Err(Error::SDKError(SDKError::Validation { .. })) => (),
+                _ => panic!("`{query}` must be rejected"),
+            }
+        }
+    }
+
+    #[test]
+    fn store_persists_the_queries() {
+        let dir = std::env::temp_dir().join(format!("oxide-todo-query-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("queries.json");
+        let store = QueryStore::open(&path).unwrap();
+        store
+            .insert("open", parse("statuses=pending,progress"))
+            .unwrap();
+        store.insert("done", parse("status=completed")).unwrap();
+        store.remove("done").unwrap();
+        let reopened = QueryStore::open(&path).unwrap();
+        assert_eq!(reopened.names(), ["open"]);
+        assert_eq!(
+            reopened.get("open").unwrap().to_string(),
+            store.get("open").unwrap().to_string()
+        );
+        // Only the store file is left, without the temporary file
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
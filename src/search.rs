@@ -0,0 +1,499 @@
+//! The search module. This module contains the fuzzy search of the user todos by title.
+//!
+//! The server title filter is a plain match, so the search fetches all the todos and ranks them locally.
+//! Each word of the query is compared with the words of the title by the Jaro-Winkler similarity, and the score
+//! of a todo is the average of the best similarity of each query word. A title which contains the whole query
+//! has the score `1.0`. The matched ranges of the title are returned, to highlight them.
+//!
+//! Use [`User::search`] for one search, or a [`SearchIndex`] to keep the todos for repeat searches.
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//! use oxide_todo_sdk::search::SearchQuery;
+//! use std::time::Duration;
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+//!     // Finds "Homework: math" even with the typo
+//!     for hit in user.search("homwork").await? {
+//!         println!("{:.2} {}", hit.score(), hit.highlight("[", "]"));
+//!     }
+//!     // Keep the todos for a minute between the searches
+//!     let mut index = user.search_index(Duration::from_secs(60));
+//!     let hits = index.search(SearchQuery::new("groceries").limit(5).min_score(0.8)).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`User::search`]: crate::types::User::search
+
+use crate::{
+    errors::Result as OxideResult,
+    types::{Todo, User, PAGE_SIZE},
+};
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+/// The Jaro-Winkler prefix scale.
+const PREFIX_SCALE: f64 = 0.1;
+/// The maximum prefix length of the Jaro-Winkler similarity.
+const MAX_PREFIX: usize = 4;
+
+/// A search query, the text and the options of the search.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[must_use]
+pub struct SearchQuery {
+    /// The searched text.
+    pub(crate) text: String,
+    /// The maximum amount of the results.
+    pub(crate) limit: usize,
+    /// The minimum score of the results, from `0.0` to `1.0`.
+    pub(crate) min_score: f64,
+}
+
+/// A todo matched by the search, with its score and the matched ranges of its title.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SearchHit {
+    /// The matched todo.
+    pub(crate) todo: Todo,
+    /// The score of the todo, from `0.0` to `1.0`.
+    pub(crate) score: f64,
+    /// The matched byte ranges of the title, sorted and not overlapping.
+    pub(crate) ranges: Vec<Range<usize>>,
+}
+
+/// A word of a title, lowercased, with its byte range in the title.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct Word {
+    /// The lowercased word.
+    text: Vec<char>,
+    /// The byte range of the word in the title.
+    range: Range<usize>,
+}
+
+/// A todo of the index, with the words of its title.
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct IndexedTodo {
+    /// The todo.
+    todo: Todo,
+    /// The words of the todo title.
+    words: Vec<Word>,
+}
+
+/// A cached index of the user todos for repeat searches. The todos are fetched again when they are older than
+/// the max age, or after [`SearchIndex::invalidate`].
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SearchIndex {
+    /// The user of the todos.
+    user: User,
+    /// The max age of the fetched todos.
+    max_age: Duration,
+    /// The fetched todos.
+    todos: Vec<IndexedTodo>,
+    /// When the todos are fetched, `None` if they are not fetched yet.
+    fetched_at: Option<Instant>,
+}
+
+impl SearchQuery {
+    /// Create a new query of the text. The default limit is 10 and the default minimum score is `0.75`.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            limit: 10,
+            min_score: 0.75,
+        }
+    }
+
+    /// Set the maximum amount of the results. (default: 10)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Set the minimum score of the results, from `0.0` to `1.0`. (default: `0.75`)
+    pub fn min_score(mut self, min_score: f64) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    /// Returns the searched text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Rank the indexed todos with the query, the best first.
+    fn rank<'a>(&self, todos: impl IntoIterator<Item = &'a IndexedTodo>) -> Vec<SearchHit> {
+        let query: Vec<Vec<char>> = words(&self.text)
+            .into_iter()
+            .map(|word| word.text)
+            .collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut hits: Vec<SearchHit> = todos
+            .into_iter()
+            .filter_map(|indexed| self.score(&query, indexed))
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(self.limit);
+        hits
+    }
+
+    /// Returns the hit of the todo, `None` if its score is below the minimum score.
+    fn score(&self, query: &[Vec<char>], indexed: &IndexedTodo) -> Option<SearchHit> {
+        if let Some(range) = find_ignore_case(&indexed.todo.title, self.text.trim()) {
+            return Some(SearchHit {
+                todo: indexed.todo.clone(),
+                score: 1.0,
+                ranges: vec![range],
+            });
+        }
+        let mut total = 0.0;
+        let mut ranges = Vec::new();
+        for query_word in query {
+            let best = indexed
+                .words
+                .iter()
+                .map(|word| (jaro_winkler(query_word, &word.text), word))
+                .max_by(|(a, _), (b, _)| a.total_cmp(b));
+            if let Some((similarity, word)) = best {
+                total += similarity;
+                if similarity >= self.min_score {
+                    ranges.push(word.range.clone());
+                }
+            }
+        }
+        let score = total / query.len() as f64;
+        if score < self.min_score {
+            return None;
+        }
+        ranges.sort_by_key(|range| range.start);
+        ranges.dedup();
+        Some(SearchHit {
+            todo: indexed.todo.clone(),
+            score,
+            ranges,
+        })
+    }
+}
+
+impl From<&str> for SearchQuery {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for SearchQuery {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl SearchHit {
+    /// Returns the matched todo.
+    pub fn todo(&self) -> &Todo {
+        &self.todo
+    }
+
+    /// Returns the matched todo, consuming the hit.
+    pub fn into_todo(self) -> Todo {
+        self.todo
+    }
+
+    /// Returns the score of the todo, from `0.0` to `1.0`.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Returns the matched byte ranges of the title, sorted and not overlapping.
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    /// Returns the title with the matched ranges between `before` and `after`, e.g. `("**", "**")` for Markdown.
+    pub fn highlight(&self, before: &str, after: &str) -> String {
+        let title = &self.todo.title;
+        let mut highlighted = String::with_capacity(title.len());
+        let mut last = 0;
+        for range in &self.ranges {
+            highlighted.push_str(&title[last..range.start]);
+            highlighted.push_str(before);
+            highlighted.push_str(&title[range.clone()]);
+            highlighted.push_str(after);
+            last = range.end;
+        }
+        highlighted.push_str(&title[last..]);
+        highlighted
+    }
+}
+
+impl IndexedTodo {
+    /// Index the words of the todo title.
+    fn new(todo: Todo) -> Self {
+        Self {
+            words: words(&todo.title),
+            todo,
+        }
+    }
+}
+
+impl SearchIndex {
+    /// Search the todos with the query, the todos are fetched first if they are older than the max age.
+    pub async fn search(&mut self, query: impl Into<SearchQuery>) -> OxideResult<Vec<SearchHit>> {
+        let is_fresh = self
+            .fetched_at
+            .map_or(false, |fetched_at| fetched_at.elapsed() < self.max_age);
+        if !is_fresh {
+            self.refresh().await?;
+        }
+        Ok(query.into().rank(&self.todos))
+    }
+
+    /// Fetch the todos again from the server.
+    pub async fn refresh(&mut self) -> OxideResult<()> {
+        let todos = self.user.todos().limit(PAGE_SIZE).all().await?;
+        self.todos = todos.into_iter().map(IndexedTodo::new).collect();
+        self.fetched_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Mark the todos as stale, they will be fetched again on the next search.
+    pub fn invalidate(&mut self) {
+        self.fetched_at = None;
+    }
+
+    /// Returns the amount of the indexed todos.
+    pub fn len(&self) -> usize {
+        self.todos.len()
+    }
+
+    /// Returns `true` if there are no indexed todos.
+    pub fn is_empty(&self) -> bool {
+        self.todos.is_empty()
+    }
+}
+
+impl User {
+    /// Search the user todos by title, the best first. See the [`search`] module.
+    ///
+    /// [`search`]: crate::search
+    pub async fn search(&self, query: impl Into<SearchQuery>) -> OxideResult<Vec<SearchHit>> {
+        let mut index = self.search_index(Duration::ZERO);
+        index.search(query).await
+    }
+
+    /// Returns a search index of the user todos, the todos are fetched again when they are older than `max_age`.
+    /// See the [`search`] module.
+    ///
+    /// [`search`]: crate::search
+    pub fn search_index(&self, max_age: Duration) -> SearchIndex {
+        SearchIndex {
+            user: self.clone(),
+            max_age,
+            todos: Vec::new(),
+            fetched_at: None,
+        }
+    }
+}
+
+/// Returns the lowercased alphanumeric words of the text, with their byte ranges.
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (idx, chr) in text.char_indices().chain(Some((text.len(), ' '))) {
+        match (start, chr.is_alphanumeric()) {
+            (None, true) => start = Some(idx),
+            (Some(word_start), false) => {
+                words.push(Word {
+                    text: text[word_start..idx].to_lowercase().chars().collect(),
+                    range: word_start..idx,
+                });
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    words
+}
+
+/// Returns the byte range of the first case-insensitive occurrence of the needle in the haystack.
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut rest = haystack[start..].char_indices();
+        for needle_chr in needle.chars() {
+            let (_, chr) = rest.next()?;
+            if !chr.to_lowercase().eq(needle_chr.to_lowercase()) {
+                return None;
+            }
+        }
+        let end = rest.next().map_or(haystack.len(), |(idx, _)| start + idx);
+        Some(start..end)
+    })
+}
+
+/// Returns the Jaro-Winkler similarity of the two words, from `0.0` to `1.0`.
+fn jaro_winkler(a: &[char], b: &[char]) -> f64 {
+    let jaro = jaro(a, b);
+    let prefix = a
+        .iter()
+        .zip(b)
+        .take(MAX_PREFIX)
+        .take_while(|(a, b)| a == b)
+        .count();
+    jaro + prefix as f64 * PREFIX_SCALE * (1.0 - jaro)
+}
+
+/// Returns the Jaro similarity of the two words, from `0.0` to `1.0`.
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, chr) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        if let Some(j) = (start..end).find(|&j| !b_matched[j] && &b[j] == chr) {
+            b_matched[j] = true;
+            a_matches.push(*chr);
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+    let b_matches = b
+        .iter()
+        .zip(&b_matched)
+        .filter_map(|(chr, matched)| matched.then_some(chr));
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(a, b)| a != b)
+        .count()
+        / 2;
+    let matches = a_matches.len() as f64;
+    (matches / a.len() as f64
+        + matches / b.len() as f64
+        + (matches - transpositions as f64) / matches)
+        / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_todo;
+
+    fn indexed(title: &str) -> IndexedTodo {
+        IndexedTodo::new(test_todo(title, "pending", 0, 0))
+    }
+
+    fn chars(word: &str) -> Vec<char> {
+        word.chars().collect()
+    }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn jaro_winkler_known_values() {
+        assert!(approx(jaro(&chars("martha"), &chars("marhta")), 0.944));
+        assert!(approx(
+            jaro_winkler(&chars("martha"), &chars("marhta")),
+            0.961
+        ));
+        assert!(approx(
+            jaro_winkler(&chars("dixon"), &chars("dicksonx")),
+            0.813
+        ));
+        assert!(approx(jaro_winkler(&chars("same"), &chars("same")), 1.0));
+        assert!(approx(jaro_winkler(&chars("abc"), &chars("xyz")), 0.0));
+    }
+
+    #[test]
+    fn jaro_empty_words() {
+        assert!(approx(jaro(&[], &[]), 1.0));
+        assert!(approx(jaro(&chars("a"), &[]), 0.0));
+        assert!(approx(jaro(&[], &chars("a")), 0.0));
+    }
+
+    #[test]
+    fn words_have_byte_ranges() {
+        let words = words("Buy  MILK, café!");
+        let texts: Vec<String> = words.iter().map(|w| w.text.iter().collect()).collect();
+        assert_eq!(texts, ["buy", "milk", "café"]);
+        let ranges: Vec<_> = words.iter().map(|w| w.range.clone()).collect();
+        assert_eq!(ranges, [0..3, 5..9, 11..16]);
+    }
+
+    #[test]
+    fn find_ignore_case_ranges() {
+        assert_eq!(find_ignore_case("Buy MILK now", "milk"), Some(4..8));
+        assert_eq!(find_ignore_case("Café au lait", "CAFÉ"), Some(0..5));
+        assert_eq!(find_ignore_case("Buy milk", "bread"), None);
+        assert_eq!(find_ignore_case("Buy milk", ""), None);
+    }
+
+    #[test]
+    fn substring_match_scores_one() {
+        let todos = [indexed("Homework: Math"), indexed("Groceries")];
+        let hits = SearchQuery::new(" homework ").rank(&todos);
+        assert_eq!(hits.len(), 1);
+        assert!(approx(hits[0].score(), 1.0));
+        assert_eq!(hits[0].ranges(), &[Range { start: 0, end: 8 }]);
+        assert_eq!(hits[0].highlight("[", "]"), "[Homework]: Math");
+    }
+
+    #[test]
+    fn fuzzy_match_ranks_and_highlights() {
+        let todos = [
+            indexed("Call the plumber"),
+            indexed("Homework: math and physics"),
+            indexed("Homewrok"),
+        ];
+        let hits = SearchQuery::new("homwork math").rank(&todos);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].todo().title(), "Homework: math and physics");
+        assert!(hits[0].score() < 1.0);
+        assert_eq!(hits[0].ranges(), [0..8, 10..14]);
+        assert_eq!(
+            hits[0].highlight("**", "**"),
+            "**Homework**: **math** and physics"
+        );
+    }
+
+    #[test]
+    fn min_score_and_limit() {
+        let todos = [indexed("Homework"), indexed("Homewrok"), indexed("Laundry")];
+        let hits = SearchQuery::new("homework").min_score(0.0).rank(&todos);
+        assert_eq!(hits.len(), 3);
+        assert!(hits.windows(2).all(|w| w[0].score() >= w[1].score()));
+        assert_eq!(hits[0].todo().title(), "Homework");
+        assert_eq!(hits[2].todo().title(), "Laundry");
+        let hits = SearchQuery::new("homework").limit(1).rank(&todos);
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[test]
+    fn empty_query_has_no_hits() {
+        let todos = [indexed("Homework")];
+        assert!(SearchQuery::new(" ,; ")
+            .min_score(0.0)
+            .rank(&todos)
+            .is_empty());
+    }
+}
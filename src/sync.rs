@@ -0,0 +1,1004 @@
+//! The sync module. This module contains an offline-first local replica of the user todos. (Requires the `sync` feature)
+//!
+//! The replica is stored in a JSON file, you can create, update and delete todos locally while offline,
+//! the changes are queued in an operation log and replayed against the server with [`Replica::sync`]
+//! when the server is reachable again. You can also run the sync in the background with [`spawn_background_sync`].
+//! ### Example
+//! ```rust |no_run
+//! use oxide_todo_sdk::Client;
+//! use oxide_todo_sdk::errors::Result as OxideResult;
+//! use oxide_todo_sdk::sync::{ConflictPolicy, Replica};
+//! use oxide_todo_sdk::types::TodoStatus;
+//!
+//! #[tokio::main]
+//! async fn main() -> OxideResult<()> {
+//!     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+//!     let mut replica = Replica::open("todos.json")?;
+//!     // Works offline, the todo is queued
+//!     replica.create("My new todo", TodoStatus::Pending)?;
+//!     // Replay the queued operations and pull the server todos
+//!     let report = replica.sync(&user, ConflictPolicy::LastUpdatedWins).await?;
+//!     if report.is_offline() {
+//!         println!("The server is unreachable, {} operations still queued", replica.pending().len());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    errors::{Error, Result as OxideResult, SDKError},
+    types::{Todo, TodoOrder, TodoOrderBy, TodoStatus, User, PAGE_SIZE},
+    validation::TitleRules,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+/// The version of the replica file format.
+const REPLICA_VERSION: u32 = 1;
+
+/// The allowed difference between the client and the server clocks, in seconds.
+/// Used to find the todo created by a create attempt whose response is lost.
+const CLOCK_SKEW: u64 = 5 * 60;
+
+/// The conflict resolution policy, used when a queued operation targets a todo that has been changed on the server.
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// The server version is kept, and the local operation is dropped.
+    ServerWins,
+    /// The local operation is applied, overwriting the server version.
+    ClientWins,
+    /// The most recent change wins, by comparing the operation time with the server `updated_at`. (default)
+    ///
+    /// The operation time is from the client clock and `updated_at` is from the server clock,
+    /// so a skew between the two clocks can make the older change win.
+    #[default]
+    LastUpdatedWins,
+}
+
+/// A todo in the local replica.
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct LocalTodo {
+    /// The todo uuid, a temporary uuid if the todo is not created on the server yet.
+    pub(crate) uuid: Uuid,
+    /// The todo title.
+    pub(crate) title: String,
+    /// The todo status.
+    pub(crate) status: TodoStatus,
+    /// Todo creation time. (Unix timestamp in seconds)
+    pub(crate) created_at: u64,
+    /// Last todo update time. (Unix timestamp in seconds)
+    pub(crate) updated_at: u64,
+    /// The last known `updated_at` of the todo on the server, `None` if the todo is not created on the server yet.
+    pub(crate) server_updated_at: Option<u64>,
+}
+
+/// A queued operation, which is not replayed against the server yet.
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /// Create a todo.
+    Create {
+        /// The temporary uuid of the todo.
+        uuid: Uuid,
+        /// The todo title.
+        title: String,
+        /// The todo status.
+        status: TodoStatus,
+        /// When the operation is queued. (Unix timestamp in seconds)
+        at: u64,
+        /// When the operation was last sent to the server, `None` if it's not sent yet. (Unix timestamp in seconds)
+        /// The server may have created the todo even if the request failed, it's looked up before creating it again.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        attempted: Option<u64>,
+    },
+    /// Update a todo.
+    Update {
+        /// The todo uuid.
+        uuid: Uuid,
+        /// The new title, `None` to keep the current title.
+        title: Option<String>,
+        /// The new status, `None` to keep the current status.
+        status: Option<TodoStatus>,
+        /// The last known `updated_at` of the todo on the server.
+        base: Option<u64>,
+        /// When the operation is queued. (Unix timestamp in seconds)
+        at: u64,
+    },
+    /// Delete a todo.
+    Delete {
+        /// The todo uuid.
+        uuid: Uuid,
+        /// The last known `updated_at` of the todo on the server.
+        base: Option<u64>,
+        /// When the operation is queued. (Unix timestamp in seconds)
+        at: u64,
+    },
+}
+
+/// A conflict found while replaying an operation.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SyncConflict {
+    /// The conflicting operation.
+    pub(crate) operation: Operation,
+    /// The server version of the todo, `None` if the todo is deleted from the server.
+    pub(crate) server: Option<Todo>,
+    /// `true` if the local operation is applied, `false` if it's dropped.
+    pub(crate) applied: bool,
+}
+
+/// The report of a sync. See [`Replica::sync`].
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SyncReport {
+    /// The amount of the applied operations.
+    pub(crate) applied: usize,
+    /// The conflicts, and how they are resolved.
+    pub(crate) conflicts: Vec<SyncConflict>,
+    /// The dropped operations, because the server or the client validation rejected them.
+    pub(crate) failed: Vec<(Operation, String)>,
+    /// The uuids of the created todos, from the temporary uuid to the server uuid.
+    pub(crate) created: HashMap<Uuid, Uuid>,
+    /// `true` if the server is unreachable, and the sync is stopped.
+    pub(crate) offline: bool,
+}
+
+/// The result of a replayed operation, applied to the replica after the requests.
+#[derive(Default)]
+struct Replayed {
+    /// The server version of the todo after the operation, `None` if there is no todo to store.
+    todo: Option<Todo>,
+    /// The server uuid of the todo, if the operation created the todo on the server.
+    created: Option<Uuid>,
+    /// The conflict found while replaying the operation.
+    conflict: Option<SyncConflict>,
+    /// `true` if the operation is applied on the server.
+    applied: bool,
+}
+
+/// The replica file content.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct ReplicaState {
+    /// The version of the file format.
+    version: u32,
+    /// The todos, by uuid.
+    todos: BTreeMap<Uuid, LocalTodo>,
+    /// The queued operations, in order.
+    log: Vec<Operation>,
+}
+
+/// An offline-first local replica of the user todos, persisted in a JSON file.
+///
+/// All the local changes are saved to the file immediately.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Replica {
+    /// The path of the replica file.
+    path: PathBuf,
+    /// The replica content.
+    state: ReplicaState,
+    /// The rules of the local todo titles.
+    title_rules: TitleRules,
+}
+
+impl LocalTodo {
+    /// Returns the uuid of the todo, a temporary uuid if the todo is not created on the server yet.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Returns the title of the todo.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the status of the todo.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status
+    }
+
+    /// Returns the creation time of the todo, as a Unix timestamp in seconds.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the last update time of the todo, as a Unix timestamp in seconds.
+    pub fn updated_at(&self) -> u64 {
+        self.updated_at
+    }
+
+    /// Returns `true` if the todo is created on the server.
+    pub fn is_synced(&self) -> bool {
+        self.server_updated_at.is_some()
+    }
+}
+
+impl From<&Todo> for LocalTodo {
+    fn from(todo: &Todo) -> Self {
+        Self {
+            uuid: todo.uuid,
+            title: todo.title.clone(),
+            status: todo.status.clone(),
+            created_at: todo.created_at,
+            updated_at: todo.updated_at,
+            server_updated_at: Some(todo.updated_at),
+        }
+    }
+}
+
+impl Operation {
+    /// Returns the uuid of the todo which the operation targets.
+    pub fn uuid(&self) -> Uuid {
+        match self {
+            Self::Create { uuid, .. } | Self::Update { uuid, .. } | Self::Delete { uuid, .. } => {
+                *uuid
+            }
+        }
+    }
+
+    /// Returns when the operation is queued, as a Unix timestamp in seconds.
+    pub fn at(&self) -> u64 {
+        match self {
+            Self::Create { at, .. } | Self::Update { at, .. } | Self::Delete { at, .. } => *at,
+        }
+    }
+
+    /// Replace the temporary uuid of the operation with the server uuid.
+    fn remap(&mut self, created: &HashMap<Uuid, Uuid>) {
+        let (Self::Create { uuid, .. } | Self::Update { uuid, .. } | Self::Delete { uuid, .. }) =
+            self;
+        if let Some(server_uuid) = created.get(uuid) {
+            *uuid = *server_uuid;
+        }
+    }
+}
+
+impl SyncConflict {
+    /// Returns the conflicting operation.
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    /// Returns the server version of the todo, `None` if the todo is deleted from the server.
+    pub fn server(&self) -> Option<&Todo> {
+        self.server.as_ref()
+    }
+
+    /// Returns `true` if the local operation is applied, `false` if it's dropped.
+    pub fn is_applied(&self) -> bool {
+        self.applied
+    }
+}
+
+impl SyncReport {
+    /// Returns the amount of the applied operations.
+    pub fn applied(&self) -> usize {
+        self.applied
+    }
+
+    /// Returns the conflicts, and how they are resolved.
+    pub fn conflicts(&self) -> &[SyncConflict] {
+        &self.conflicts
+    }
+
+    /// Returns the dropped operations with the error message, because the server or the client validation
+    /// rejected them. For example a title rejected by the client [`TitleRules`] or a transition forbidden by its
+    /// [`Workflow`].
+    ///
+    /// [`Workflow`]: crate::types::Workflow
+    pub fn failed(&self) -> &[(Operation, String)] {
+        &self.failed
+    }
+
+    /// Returns the uuids of the created todos, from the temporary uuid to the server uuid.
+    pub fn created(&self) -> &HashMap<Uuid, Uuid> {
+        &self.created
+    }
+
+    /// Returns `true` if the server is unreachable, the remaining operations are still queued.
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+}
+
+impl Replica {
+    /// Open the replica file, it will be created on the first save if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> OxideResult<Self> {
+        let path = path.as_ref().to_owned();
+        let state = if path.exists() {
+            let state: ReplicaState = serde_json::from_slice(&fs::read(&path)?)?;
+            if state.version != REPLICA_VERSION {
+                return Err(Error::JSONError(serde::de::Error::custom(format!(
+                    "unsupported replica version {}, expected {REPLICA_VERSION}",
+                    state.version
+                ))));
+            }
+            state
+        } else {
+            ReplicaState {
+                version: REPLICA_VERSION,
+                ..Default::default()
+            }
+        };
+        Ok(Self {
+            path,
+            state,
+            title_rules: TitleRules::default(),
+        })
+    }
+
+    /// Set the rules of the local todo titles, the titles are validated before the changes are queued.
+    /// Use the rules of the client, so a queued change is not rejected while it's replayed.
+    /// (default: [`TitleRules::default`])
+    pub fn with_title_rules(mut self, title_rules: TitleRules) -> Self {
+        self.title_rules = title_rules;
+        self
+    }
+
+    /// Returns the path of the replica file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the local todos, ordered by uuid.
+    pub fn todos(&self) -> impl Iterator<Item = &LocalTodo> {
+        self.state.todos.values()
+    }
+
+    /// Returns the local todo by uuid.
+    pub fn get(&self, uuid: Uuid) -> Option<&LocalTodo> {
+        self.state.todos.get(&uuid)
+    }
+
+    /// Returns the queued operations, in order.
+    pub fn pending(&self) -> &[Operation] {
+        &self.state.log
+    }
+
+    /// Create a todo locally, and queue it to be created on the server. Returns the temporary uuid of the todo.
+    /// Returns [`SDKError::Validation`] if the title is rejected by the title rules.
+    pub fn create(&mut self, title: impl Into<String>, status: TodoStatus) -> OxideResult<Uuid> {
+        let title = self.title_rules.validate(&title.into())?;
+        let uuid = Uuid::new_v4();
+        let at = now();
+        self.state.todos.insert(
+            uuid,
+            LocalTodo {
+                uuid,
+                title: title.clone(),
+                status: status.clone(),
+                created_at: at,
+                updated_at: at,
+                server_updated_at: None,
+            },
+        );
+        self.state.log.push(Operation::Create {
+            uuid,
+            title,
+            status,
+            at,
+            attempted: None,
+        });
+        self.save()?;
+        Ok(uuid)
+    }
+
+    /// Update a todo locally, and queue the update to the server. `None` to keep the current value.
+    /// Returns [`SDKError::Validation`] if the title is rejected by the title rules.
+    pub fn update(
+        &mut self,
+        uuid: Uuid,
+        title: Option<String>,
+        status: Option<TodoStatus>,
+    ) -> OxideResult<()> {
+        let title = match title {
+            Some(title) => Some(self.title_rules.validate(&title)?),
+            None => None,
+        };
+        let at = now();
+        let todo = self.local_mut(uuid)?;
+        if let Some(title) = &title {
+            todo.title = title.clone();
+        }
+        if let Some(status) = &status {
+            todo.status = status.clone();
+        }
+        todo.updated_at = at;
+        let base = todo.server_updated_at;
+        self.state.log.push(Operation::Update {
+            uuid,
+            title,
+            status,
+            base,
+            at,
+        });
+        self.save()
+    }
+
+    /// Delete a todo locally, and queue the deletion to the server.
+    pub fn delete(&mut self, uuid: Uuid) -> OxideResult<()> {
+        let base = self.local_mut(uuid)?.server_updated_at;
+        self.state.todos.remove(&uuid);
+        self.state.log.push(Operation::Delete {
+            uuid,
+            base,
+            at: now(),
+        });
+        self.save()
+    }
+
+    /// Write the replica to its file.
+    pub fn save(&self) -> OxideResult<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(&self.state)?)?;
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Replay the queued operations against the server, then replace the local todos with the server todos.
+    ///
+    /// If the server is unreachable, the sync will stop and the remaining operations will stay queued,
+    /// see [`SyncReport::is_offline`]. The operations rejected by the server or the client validation are dropped,
+    /// see [`SyncReport::failed`].
+    /// The queued operations which are not replayed yet are kept over the server todos.
+    pub async fn sync(&mut self, user: &User, policy: ConflictPolicy) -> OxideResult<SyncReport> {
+        let mut report = SyncReport::default();
+        while let Some((operation, local)) = self.begin_replay()? {
+            let result = replay(user, policy, &operation, local).await;
+            if !self.finish_replay(&operation, result, &mut report)? {
+                break;
+            }
+        }
+        if !report.offline {
+            let result = user.todos().fresh().limit(PAGE_SIZE).all().await;
+            self.pull(result, &mut report)?;
+        }
+        Ok(report)
+    }
+
+    /// Same as [`Replica::sync`], for a replica shared behind a mutex. The lock is only held while the replica
+    /// is read or written, never during the requests, so the local changes are not blocked by the sync.
+    /// The local changes made while syncing are queued, and replayed by this sync or the next one.
+    pub async fn sync_shared(
+        replica: &tokio::sync::Mutex<Self>,
+        user: &User,
+        policy: ConflictPolicy,
+    ) -> OxideResult<SyncReport> {
+        let mut report = SyncReport::default();
+        loop {
+            let next = replica.lock().await.begin_replay()?;
+            let (operation, local) = match next {
+                Some(next) => next,
+                None => break,
+            };
+            let result = replay(user, policy, &operation, local).await;
+            if !replica
+                .lock()
+                .await
+                .finish_replay(&operation, result, &mut report)?
+            {
+                break;
+            }
+        }
+        if !report.offline {
+            let result = user.todos().fresh().limit(PAGE_SIZE).all().await;
+            replica.lock().await.pull(result, &mut report)?;
+        }
+        Ok(report)
+    }
+
+    /// Returns the first queued operation and the local version of its todo, `None` if there are no operations.
+    ///
+    /// A create is marked as attempted and saved before it's sent, so if the server commits it but the response
+    /// is lost, the next replay finds the created todo instead of creating it again. The returned operation is
+    /// the operation before the mark.
+    fn begin_replay(&mut self) -> OxideResult<Option<(Operation, Option<LocalTodo>)>> {
+        let operation = match self.state.log.first_mut() {
+            Some(operation) => operation,
+            None => return Ok(None),
+        };
+        let next = operation.clone();
+        if let Operation::Create { attempted, .. } = operation {
+            *attempted = Some(now());
+            self.save()?;
+        }
+        let local = self.state.todos.get(&next.uuid()).cloned();
+        Ok(Some((next, local)))
+    }
+
+    /// Apply the result of the replayed operation to the replica, and remove the operation from the log.
+    /// Returns `false` if the server is unreachable, and the sync has to stop.
+    fn finish_replay(
+        &mut self,
+        operation: &Operation,
+        result: OxideResult<Replayed>,
+        report: &mut SyncReport,
+    ) -> OxideResult<bool> {
+        match result {
+            Err(err) if err.is_offline() => {
+                report.offline = true;
+                return Ok(false);
+            }
+            Err(Error::APIError(err)) => report.failed.push((operation.clone(), err.to_string())),
+            // Rejected by the client validation, replaying it again will fail the same way
+            Err(Error::SDKError(err)) => report.failed.push((operation.clone(), err.to_string())),
+            Err(err) => return Err(err),
+            Ok(replayed) => {
+                if let Some(conflict) = replayed.conflict {
+                    report.conflicts.push(conflict);
+                }
+                if let Some(server_uuid) = replayed.created {
+                    self.state.todos.remove(&operation.uuid());
+                    report.created.insert(operation.uuid(), server_uuid);
+                }
+                if let Some(todo) = replayed.todo {
+                    self.state.todos.insert(todo.uuid, (&todo).into());
+                }
+                if replayed.applied {
+                    report.applied += 1;
+                }
+            }
+        }
+        // The local changes are only appended to the log, so the replayed operation is still the first one
+        let replayed =
+            |first: &Operation| first.uuid() == operation.uuid() && first.at() == operation.at();
+        if self.state.log.first().map_or(false, replayed) {
+            self.state.log.remove(0);
+        }
+        // Keep the queued operations consistent with the created todos
+        for operation in &mut self.state.log {
+            operation.remap(&report.created);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Replace the local todos with the server todos, keeping the local version of the todos of the queued operations.
+    fn pull(&mut self, result: OxideResult<Vec<Todo>>, report: &mut SyncReport) -> OxideResult<()> {
+        match result {
+            Ok(todos) => {
+                let mut todos: BTreeMap<Uuid, LocalTodo> =
+                    todos.iter().map(|t| (t.uuid, t.into())).collect();
+                for operation in &self.state.log {
+                    let uuid = operation.uuid();
+                    match self.state.todos.get(&uuid) {
+                        Some(local) => todos.insert(uuid, local.clone()),
+                        None => todos.remove(&uuid),
+                    };
+                }
+                self.state.todos = todos;
+            }
+            Err(err) if err.is_offline() => report.offline = true,
+            Err(err) => return Err(err),
+        }
+        self.save()
+    }
+
+    /// Returns the local todo by uuid, or an error if it's not in the replica.
+    fn local_mut(&mut self, uuid: Uuid) -> OxideResult<&mut LocalTodo> {
+        self.state.todos.get_mut(&uuid).ok_or_else(|| {
+            SDKError::missing_field(format!("`uuid` no local todo with uuid `{uuid}`")).into()
+        })
+    }
+}
+
+/// Run [`Replica::sync_shared`] every `interval` in the background, `on_report` is called with the result of each sync.
+/// The replica lock is not held during the requests, so the replica can be changed while it's synced.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+/// use oxide_todo_sdk::sync::{spawn_background_sync, ConflictPolicy, Replica};
+/// use oxide_todo_sdk::types::TodoStatus;
+/// use std::{sync::Arc, time::Duration};
+/// use tokio::sync::Mutex;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+///     let replica = Arc::new(Mutex::new(Replica::open("todos.json")?));
+///     let handle = spawn_background_sync(
+///         Arc::clone(&replica),
+///         user,
+///         ConflictPolicy::ServerWins,
+///         Duration::from_secs(30),
+///         |result| if let Err(err) = result { eprintln!("Sync failed: {err}") },
+///     );
+///     // Use the replica
+///     replica.lock().await.create("My new todo", TodoStatus::Pending)?;
+///     Ok(())
+/// }
+/// ```
+pub fn spawn_background_sync(
+    replica: Arc<tokio::sync::Mutex<Replica>>,
+    user: User,
+    policy: ConflictPolicy,
+    interval: Duration,
+    mut on_report: impl FnMut(OxideResult<SyncReport>) + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let result = Replica::sync_shared(&replica, &user, policy).await;
+            on_report(result);
+            tokio::time::sleep(interval).await;
+        }
+    })
+}
+
+/// Replay one operation against the server, `local` is the local version of the todo when the replay started.
+async fn replay(
+    user: &User,
+    policy: ConflictPolicy,
+    operation: &Operation,
+    local: Option<LocalTodo>,
+) -> OxideResult<Replayed> {
+    match operation {
+        Operation::Create {
+            title,
+            status,
+            attempted,
+            ..
+        } => {
+            let committed = match attempted {
+                Some(attempted) => committed_create(user, title, *attempted).await?,
+                None => None,
+            };
+            let todo = match committed {
+                Some(todo) => todo,
+                None => user.create_todo(title).set_status(status.clone()).await?,
+            };
+            Ok(Replayed {
+                created: Some(todo.uuid),
+                todo: Some(todo),
+                conflict: None,
+                applied: true,
+            })
+        }
+        Operation::Update {
+            uuid,
+            title,
+            status,
+            base,
+            at,
+        } => {
+            let server = server_version(user, *uuid).await?;
+            let changed = match (&server, base) {
+                (Some(server), Some(base)) => server.updated_at != *base,
+                // The todo is created offline, there is no known server version
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            let apply = !changed || resolve(policy, *at, server.as_ref());
+            let conflict = changed.then(|| SyncConflict {
+                operation: operation.clone(),
+                server: server.clone(),
+                applied: apply,
+            });
+            let (todo, created) = match (apply, server, local) {
+                (true, Some(_), _) => {
+                    let mut patch = user.update_todo(*uuid);
+                    patch.title = title.clone();
+                    patch.status = status.clone();
+                    (Some(patch.await?), None)
+                }
+                (true, None, Some(local)) => {
+                    // The todo is deleted from the server, recreate it from the local version
+                    let todo = user
+                        .create_todo(local.title)
+                        .set_status(local.status)
+                        .await?;
+                    let uuid = todo.uuid;
+                    (Some(todo), Some(uuid))
+                }
+                _ => (None, None),
+            };
+            Ok(Replayed {
+                applied: todo.is_some(),
+                todo,
+                created,
+                conflict,
+            })
+        }
+        Operation::Delete { uuid, base, at } => {
+            let mut replayed = Replayed::default();
+            // Nothing to do if the todo is already deleted from the server
+            if let Some(server) = server_version(user, *uuid).await? {
+                let changed = base.map_or(false, |base| server.updated_at != base);
+                let apply = !changed || resolve(policy, *at, Some(&server));
+                if changed {
+                    replayed.conflict = Some(SyncConflict {
+                        operation: operation.clone(),
+                        server: Some(server.clone()),
+                        applied: apply,
+                    });
+                }
+                if apply {
+                    server.delete().await?;
+                    replayed.applied = true;
+                }
+            }
+            Ok(replayed)
+        }
+    }
+}
+
+/// Returns the todo created by a previous attempt of a create, if the server committed it.
+/// The todo with the same title created since the attempt, the server clock may differ by [`CLOCK_SKEW`].
+async fn committed_create(user: &User, title: &str, attempted: u64) -> OxideResult<Option<Todo>> {
+    Ok(user
+        .todos()
+        .fresh()
+        .title(title)
+        .order(TodoOrder::Newer)
+        .order_by(TodoOrderBy::CreatedAt)
+        .limit(PAGE_SIZE)
+        .await?
+        .into_iter()
+        .find(|todo| todo.title == title && todo.created_at + CLOCK_SKEW >= attempted))
+}
+
+/// Returns the server version of the todo, `None` if the todo is deleted from the server.
+async fn server_version(user: &User, uuid: Uuid) -> OxideResult<Option<Todo>> {
+    match user.fresh_todo_by_uuid(uuid).await {
+        Ok(todo) => Ok(Some(todo)),
+        Err(err) if err.is_not_found() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Returns `true` if the local operation wins the conflict, with the given policy.
+fn resolve(policy: ConflictPolicy, at: u64, server: Option<&Todo>) -> bool {
+    match policy {
+        ConflictPolicy::ServerWins => false,
+        ConflictPolicy::ClientWins => true,
+        ConflictPolicy::LastUpdatedWins => server.map_or(false, |server| at > server.updated_at),
+    }
+}
+
+/// Returns the current time as a Unix timestamp in seconds.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_todo;
+
+    /// A replica in a new temporary directory, removed with [`remove`].
+    fn replica() -> Replica {
+        let dir = std::env::temp_dir().join(format!("oxide-todo-replica-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        Replica::open(dir.join("replica.json")).unwrap()
+    }
+
+    fn remove(replica: Replica) {
+        fs::remove_dir_all(replica.path().parent().unwrap()).unwrap();
+    }
+
+    fn server_todo(uuid: Uuid, title: &str, updated_at: u64) -> Todo {
+        let mut todo = test_todo(title, "pending", updated_at, updated_at);
+        todo.uuid = uuid;
+        todo
+    }
+
+    fn api_error(status: u16) -> Error {
+        serde_json::from_value::<crate::errors::ErrorMessage>(serde_json::json!({
+            "message": "rejected",
+            "status": status,
+        }))
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn local_changes_are_validated_before_queued() {
+        let mut replica = replica().with_title_rules(TitleRules::new().max_len(5));
+        assert!(replica.create(" ", TodoStatus::Pending).is_err());
+        assert!(replica.create("Too long", TodoStatus::Pending).is_err());
+        let uuid = replica.create(" Milk ", TodoStatus::Pending).unwrap();
+        assert_eq!(replica.get(uuid).unwrap().title(), "Milk");
+        assert!(replica.update(uuid, Some(String::new()), None).is_err());
+        assert_eq!(replica.pending().len(), 1);
+        remove(replica);
+    }
+
+    #[test]
+    fn begin_replay_marks_the_create_as_attempted() {
+        let mut replica = replica();
+        assert!(replica.begin_replay().unwrap().is_none());
+        let uuid = replica.create("Milk", TodoStatus::Pending).unwrap();
+        let (operation, local) = replica.begin_replay().unwrap().unwrap();
+        // The returned operation is the operation before the mark
+        assert!(matches!(
+            operation,
+            Operation::Create {
+                attempted: None,
+                ..
+            }
+        ));
+        assert_eq!(local.unwrap().uuid(), uuid);
+        // The mark is saved, so it survives a crash while the request is sent
+        let reopened = Replica::open(replica.path()).unwrap();
+        assert!(matches!(
+            reopened.pending(),
+            [Operation::Create {
+                attempted: Some(_),
+                ..
+            }]
+        ));
+        remove(replica);
+    }
+
+    #[test]
+    fn finish_replay_remaps_the_created_todo() {
+        let mut replica = replica();
+        let local_uuid = replica.create("Milk", TodoStatus::Pending).unwrap();
+        replica
+            .update(local_uuid, None, Some(TodoStatus::Completed))
+            .unwrap();
+        let (operation, _) = replica.begin_replay().unwrap().unwrap();
+        let server_uuid = Uuid::new_v4();
+        let mut report = SyncReport::default();
+        let replayed = Replayed {
+            todo: Some(server_todo(server_uuid, "Milk", 10)),
+            created: Some(server_uuid),
+            conflict: None,
+            applied: true,
+        };
+        assert!(replica
+            .finish_replay(&operation, Ok(replayed), &mut report)
+            .unwrap());
+        assert_eq!(report.applied(), 1);
+        assert_eq!(report.created().get(&local_uuid), Some(&server_uuid));
+        assert!(replica.get(local_uuid).is_none());
+        assert!(replica.get(server_uuid).unwrap().is_synced());
+        // The queued update targets the server uuid
+        assert_eq!(replica.pending().len(), 1);
+        assert_eq!(replica.pending()[0].uuid(), server_uuid);
+        remove(replica);
+    }
+
+    #[test]
+    fn finish_replay_drops_the_rejected_operations() {
+        let mut replica = replica();
+        let first = replica.create("Milk", TodoStatus::Pending).unwrap();
+        let second = replica.create("Bread", TodoStatus::Pending).unwrap();
+        let third = replica.create("Eggs", TodoStatus::Pending).unwrap();
+        let mut report = SyncReport::default();
+        let errors = [
+            api_error(400),
+            SDKError::validation("title", "must not be empty").into(),
+            SDKError::from(crate::errors::InvalidTransition {
+                from: TodoStatus::Cancelled,
+                to: TodoStatus::Completed,
+                allowed: vec![TodoStatus::Pending],
+            })
+            .into(),
+        ];
+        for err in errors {
+            let (operation, _) = replica.begin_replay().unwrap().unwrap();
+            assert!(replica
+                .finish_replay(&operation, Err(err), &mut report)
+                .unwrap());
+        }
+        let failed: Vec<Uuid> = report.failed().iter().map(|(op, _)| op.uuid()).collect();
+        assert_eq!(failed, [first, second, third]);
+        assert!(replica.pending().is_empty());
+        assert_eq!(report.applied(), 0);
+        remove(replica);
+    }
+
+    #[test]
+    fn finish_replay_keeps_the_operation_on_other_errors() {
+        let mut replica = replica();
+        replica.create("Milk", TodoStatus::Pending).unwrap();
+        let (operation, _) = replica.begin_replay().unwrap().unwrap();
+        let err = serde_json::from_str::<Todo>("{").unwrap_err().into();
+        let mut report = SyncReport::default();
+        assert!(replica
+            .finish_replay(&operation, Err(err), &mut report)
+            .is_err());
+        assert_eq!(replica.pending().len(), 1);
+        assert!(report.failed().is_empty());
+        remove(replica);
+    }
+
+    #[tokio::test]
+    async fn finish_replay_stops_when_offline() {
+        let mut replica = replica();
+        replica.create("Milk", TodoStatus::Pending).unwrap();
+        let (operation, _) = replica.begin_replay().unwrap().unwrap();
+        // Nothing listens on the port 1, so the connection is refused
+        let err: Error = reqwest::get("http://127.0.0.1:1").await.unwrap_err().into();
+        assert!(err.is_offline());
+        let mut report = SyncReport::default();
+        assert!(!replica
+            .finish_replay(&operation, Err(err), &mut report)
+            .unwrap());
+        assert!(report.is_offline());
+        assert_eq!(replica.pending().len(), 1);
+        remove(replica);
+    }
+
+    #[test]
+    fn finish_replay_keeps_the_local_changes_made_while_replaying() {
+        let mut replica = replica();
+        let uuid = replica.create("Milk", TodoStatus::Pending).unwrap();
+        let (operation, _) = replica.begin_replay().unwrap().unwrap();
+        // Queued while the create is sent
+        replica.delete(uuid).unwrap();
+        let server_uuid = Uuid::new_v4();
+        let replayed = Replayed {
+            todo: Some(server_todo(server_uuid, "Milk", 10)),
+            created: Some(server_uuid),
+            conflict: None,
+            applied: true,
+        };
+        let mut report = SyncReport::default();
+        replica
+            .finish_replay(&operation, Ok(replayed), &mut report)
+            .unwrap();
+        assert!(matches!(
+            replica.pending(),
+            [Operation::Delete { uuid, .. }] if *uuid == server_uuid
+        ));
+        remove(replica);
+    }
+
+    #[test]
+    fn pull_keeps_the_queued_local_versions() {
+        let mut replica = replica();
+        let local = replica.create("Local", TodoStatus::Pending).unwrap();
+        let (kept, deleted, gone) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut report = SyncReport::default();
+        let server = vec![
+            server_todo(kept, "Server", 1),
+            server_todo(deleted, "Deleted", 1),
+            server_todo(gone, "Gone", 1),
+        ];
+        replica.pull(Ok(server), &mut report).unwrap();
+        replica.delete(deleted).unwrap();
+        replica
+            .update(kept, Some("Changed".to_owned()), None)
+            .unwrap();
+        // The server doesn't have the `gone` todo anymore, and the queued operations are not replayed yet
+        let server = vec![
+            server_todo(kept, "Server", 2),
+            server_todo(deleted, "Deleted", 2),
+        ];
+        replica.pull(Ok(server), &mut report).unwrap();
+        let titles: Vec<(Uuid, &str)> = replica.todos().map(|t| (t.uuid(), t.title())).collect();
+        let mut expected = vec![(kept, "Changed"), (local, "Local")];
+        expected.sort();
+        assert_eq!(titles, expected);
+        remove(replica);
+    }
+
+    #[test]
+    fn conflict_resolution() {
+        let server = server_todo(Uuid::new_v4(), "Milk", 100);
+        for (policy, at, server, applied) in [
+            (ConflictPolicy::ServerWins, 200, Some(&server), false),
+            (ConflictPolicy::ClientWins, 0, Some(&server), true),
+            (ConflictPolicy::ClientWins, 0, None, true),
+            (ConflictPolicy::LastUpdatedWins, 101, Some(&server), true),
+            (ConflictPolicy::LastUpdatedWins, 100, Some(&server), false),
+            (ConflictPolicy::LastUpdatedWins, 200, None, false),
+        ] {
+            assert_eq!(resolve(policy, at, server), applied);
+        }
+    }
+}
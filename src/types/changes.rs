@@ -0,0 +1,37 @@
+use super::Todo;
+
+/// The todos changed since a checkpoint. See [`User::changes_since`].
+///
+/// [`User::changes_since`]: crate::types::User::changes_since
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ChangeSet {
+    /// The created or updated todos, newest updated first.
+    pub(crate) updated: Vec<Todo>,
+    /// The new checkpoint. (Unix timestamp in seconds)
+    pub(crate) checkpoint: u64,
+}
+
+impl ChangeSet {
+    /// Returns the created or updated todos since the checkpoint, newest updated first.
+    pub fn updated(&self) -> &[Todo] {
+        &self.updated
+    }
+
+    /// Returns the new checkpoint, pass it to the next [`User::changes_since`] call.
+    ///
+    /// [`User::changes_since`]: crate::types::User::changes_since
+    pub fn checkpoint(&self) -> u64 {
+        self.checkpoint
+    }
+
+    /// Returns `true` if there is no changed todo.
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty()
+    }
+
+    /// Returns the changed todos, consuming the change set.
+    pub fn into_updated(self) -> Vec<Todo> {
+        self.updated
+    }
+}
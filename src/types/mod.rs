@@ -1,9 +1,40 @@
 //! The types module. This module contains all the types used by the oxide todo SDK.
 
+mod changes;
+mod snapshot;
+mod stats;
 mod todo;
+mod todo_builder;
+mod todo_diff;
+mod todo_filter;
 mod todos;
 mod user;
+#[cfg(feature = "watch")]
+mod watch;
+mod workflow;
 
+pub use changes::*;
+pub use snapshot::*;
+pub use stats::*;
 pub use todo::*;
+pub use todo_builder::*;
+pub use todo_diff::*;
+pub use todo_filter::*;
 pub use todos::*;
 pub use user::*;
+#[cfg(feature = "watch")]
+pub use watch::*;
+pub use workflow::*;
+
+/// Returns a todo as it's returned by the server, for the tests.
+#[cfg(test)]
+pub(crate) fn test_todo(title: &str, status: &str, created_at: u64, updated_at: u64) -> Todo {
+    serde_json::from_value(serde_json::json!({
+        "uuid": uuid::Uuid::new_v4(),
+        "title": title,
+        "status": status,
+        "created_at": created_at,
+        "updated_at": updated_at,
+    }))
+    .unwrap()
+}
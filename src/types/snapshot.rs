@@ -0,0 +1,436 @@
+use super::{todos::DEFAULT_LIMIT, Todo, TodoOrder, TodoOrderBy, TodoStatus, Todos, User};
+use crate::{
+    errors::{Result as OxideResult, SDKError},
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io::Write, path::Path};
+use uuid::Uuid;
+
+/// A serializable snapshot of a fetched todo, without the client state (the base url, the token, etc.)
+///
+/// Store it in your own cache or database, then reattach it to a user with [`User::attach_todo`].
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::types::TodoRecord;
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+/// use uuid::Uuid;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+///     let todo = user.todo_by_uuid(Uuid::new_v4()).await?;
+///     let json = serde_json::to_string(&todo.record())?;
+///     // Later
+///     let record: TodoRecord = serde_json::from_str(&json)?;
+///     let todo = user.attach_todo(record);
+///     todo.delete().await
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct TodoRecord {
+    /// The todo uuid.
+    pub(crate) uuid: Uuid,
+    /// The todo title.
+    pub(crate) title: String,
+    /// The todo status.
+    pub(crate) status: TodoStatus,
+    /// Todo creation time. (Unix timestamp in seconds)
+    pub(crate) created_at: u64,
+    /// Last todo update time. (Unix timestamp in seconds)
+    pub(crate) updated_at: u64,
+}
+
+/// A serializable session of a logged in user, the server base url, the username and the token.
+///
+/// Store it to keep the user logged in, then resume it with [`Client::resume`].
+/// [`Session::save`] and [`Session::load`] store it in a JSON file which only the user can read.
+/// ### Note
+/// The session contains the user token, store it as a secret. The token is redacted from the `Debug` output.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::types::Session;
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let client = Client::new("http://localhost:8080");
+///     let user = client.login("username", "password").await?;
+///     let json = serde_json::to_string(&user.session())?;
+///     // Later
+///     let session: Session = serde_json::from_str(&json)?;
+///     let user = client.resume(session)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// The base url of the server.
+    pub(crate) base_url: String,
+    /// The username of the user, `None` if the user is logged in by token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) username: Option<String>,
+    /// The user token.
+    pub(crate) token: String,
+}
+
+/// A serializable snapshot of a todos query, the limit, the offset, the order and the server filters.
+///
+/// Store it to run the same query later, reattach it to a user with [`User::attach_todos`].
+/// The local filter of [`Todos::filter`] is not part of the snapshot, because it can't be serialized.
+/// The missing fields are the defaults of [`Todos`].
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(default)]
+pub struct TodosQuery {
+    /// The limit of the todos.
+    pub(crate) limit: usize,
+    /// The offset of the todos.
+    pub(crate) offset: usize,
+    /// The order of the todos.
+    pub(crate) order: TodoOrder,
+    /// The order by of the todos.
+    pub(crate) order_by: TodoOrderBy,
+    /// The status filter of the todos.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) status: Option<TodoStatus>,
+    /// The statuses of the todos, see [`Todos::statuses`]. Empty for one status or all the statuses.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) statuses: Vec<TodoStatus>,
+    /// The title filter of the todos.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title: Option<String>,
+}
+
+impl Default for TodosQuery {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            offset: 0,
+            order: TodoOrder::default(),
+            order_by: TodoOrderBy::default(),
+            status: None,
+            statuses: Vec::new(),
+            title: None,
+        }
+    }
+}
+
+impl TodoRecord {
+    /// Returns the todo uuid.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Returns the todo title.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the todo status.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status
+    }
+
+    /// Returns the creation time of the todo, as a Unix timestamp in seconds.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the last update time of the todo, as a Unix timestamp in seconds.
+    pub fn updated_at(&self) -> u64 {
+        self.updated_at
+    }
+}
+
+impl Session {
+    /// Returns the base url of the server.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the username of the user, if it's known.
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// Returns the user token.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Load the session from the JSON file, `None` if the file doesn't exist.
+    pub fn load(path: impl AsRef<Path>) -> OxideResult<Option<Self>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+    }
+
+    /// Write the session to the JSON file, the parent directory must exist.
+    ///
+    /// The session is written to a temporary file of this process first, then it's renamed to the file, so a crash
+    /// never leaves a partially written session. On Unix the file is created with the `0o600` permissions, so the
+    /// token is never readable by the other users.
+    pub fn save(&self, path: impl AsRef<Path>) -> OxideResult<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        let write = || -> OxideResult<()> {
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut file = options.open(&tmp_path)?;
+            // The mode is only used when the file is created, fix the permissions of an old temporary file
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            }
+            file.write_all(&serde_json::to_vec_pretty(self)?)?;
+            fs::rename(&tmp_path, path)?;
+            Ok(())
+        };
+        write().map_err(|err| {
+            let _ = fs::remove_file(&tmp_path);
+            err
+        })
+    }
+}
+
+#[cfg(feature = "debug")]
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("base_url", &self.base_url)
+            .field("username", &self.username)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+impl TodosQuery {
+    /// Returns the limit of the todos.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the offset of the todos.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the order of the todos.
+    pub fn order(&self) -> &TodoOrder {
+        &self.order
+    }
+
+    /// Returns the order by of the todos.
+    pub fn order_by(&self) -> &TodoOrderBy {
+        &self.order_by
+    }
+
+    /// Returns the status filter of the todos, if there is one.
+    pub fn status(&self) -> Option<&TodoStatus> {
+        self.status.as_ref()
+    }
+
+    /// Returns the statuses of the todos, empty for one status or all the statuses.
+    pub fn statuses(&self) -> &[TodoStatus] {
+        &self.statuses
+    }
+
+    /// Returns the title filter of the todos, if there is one.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+}
+
+impl Todo {
+    /// Returns a serializable snapshot of the todo. See [`TodoRecord`].
+    pub fn record(&self) -> TodoRecord {
+        TodoRecord {
+            uuid: self.uuid,
+            title: self.title.clone(),
+            status: self.status.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl Todos {
+    /// Returns a serializable snapshot of the query, without the local filter. See [`TodosQuery`].
+    pub fn query(&self) -> TodosQuery {
+        TodosQuery {
+            limit: self.limit,
+            offset: self.offset,
+            order: self.order.clone(),
+            order_by: self.order_by.clone(),
+            status: self.status.clone(),
+            statuses: self.statuses.clone(),
+            title: self.title.clone(),
+        }
+    }
+}
+
+impl User {
+    /// Returns a serializable session of the user. See [`Session`].
+    pub fn session(&self) -> Session {
+        Session {
+            base_url: self.base_url.clone(),
+            username: self.name.clone(),
+            token: self.token.clone(),
+        }
+    }
+
+    /// Reattach a todo snapshot to the user, this will not make a request to the server. See [`TodoRecord`].
+    pub fn attach_todo(&self, record: TodoRecord) -> Todo {
+        Todo {
+            base_url: self.base_url.clone(),
+            token: self.token.clone(),
+            cache: self.cache.clone(),
+            timeouts: self.timeouts,
+            workflow: self.workflow.clone(),
+            title_rules: self.title_rules,
+            uuid: record.uuid,
+            title: record.title,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            status: record.status,
+        }
+    }
+
+    /// Reattach a todos query snapshot to the user, await it to fetch the todos. See [`TodosQuery`].
+    pub fn attach_todos(&self, query: TodosQuery) -> Todos {
+        let mut todos = self
+            .todos()
+            .limit(query.limit)
+            .offset(query.offset)
+            .order(query.order)
+            .order_by(query.order_by);
+        if let Some(status) = query.status {
+            todos = todos.status(status);
+        }
+        if !query.statuses.is_empty() {
+            todos = todos.statuses(&query.statuses);
+        }
+        if let Some(title) = query.title {
+            todos = todos.title(title);
+        }
+        todos
+    }
+}
+
+impl Client {
+    /// Resume the session with the client settings (the cache, the timeouts, etc.), this will not make a request
+    /// to the server. See [`Session`].
+    ///
+    /// Returns [`SDKError::BaseUrlMismatch`] if the session is of another server.
+    pub fn resume(&self, session: Session) -> OxideResult<User> {
+        if session.base_url.trim_end_matches('/') != self.base_url().trim_end_matches('/') {
+            return Err(SDKError::BaseUrlMismatch {
+                expected: self.base_url().to_owned(),
+                found: session.base_url,
+            }
+            .into());
+        }
+        let mut user = self.login_by_token(session.token);
+        user.name = session.username;
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn todos_query_missing_fields_are_the_defaults() {
+        let query: TodosQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.limit(), DEFAULT_LIMIT);
+        assert_eq!(query.offset(), 0);
+        assert!(query.status().is_none());
+        assert!(query.statuses().is_empty());
+        assert!(query.title().is_none());
+        let query: TodosQuery = serde_json::from_str(r#"{"offset": 20}"#).unwrap();
+        assert_eq!((query.limit(), query.offset()), (DEFAULT_LIMIT, 20));
+    }
+
+    #[test]
+    fn todos_query_round_trip() {
+        let query = TodosQuery {
+            limit: 5,
+            offset: 1,
+            status: Some(TodoStatus::Unknown("archived".to_owned())),
+            title: Some("Homework".to_owned()),
+            ..TodosQuery::default()
+        };
+        let json = serde_json::to_string(&query).unwrap();
+        let parsed: TodosQuery = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn session_username_is_optional() {
+        let session: Session =
+            serde_json::from_str(r#"{"base_url": "http://localhost:8080", "token": "secret"}"#)
+                .unwrap();
+        assert!(session.username().is_none());
+        assert!(!serde_json::to_string(&session)
+            .unwrap()
+            .contains("username"));
+    }
+
+    #[test]
+    fn session_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("oxide-todo-session-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        assert!(Session::load(&path).unwrap().is_none());
+        let session = Session {
+            base_url: "http://localhost:8080".to_owned(),
+            username: Some("awiteb".to_owned()),
+            token: "secret".to_owned(),
+        };
+        session.save(&path).unwrap();
+        Session {
+            token: "new secret".to_owned(),
+            ..session.clone()
+        }
+        .save(&path)
+        .unwrap();
+        let loaded = Session::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.token(), "new secret");
+        assert_eq!(loaded.username(), session.username());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        // Only the session file is left, without the temporary file
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn session_debug_redacts_the_token() {
+        let session = Session {
+            base_url: "http://localhost:8080".to_owned(),
+            username: Some("awiteb".to_owned()),
+            token: "secret".to_owned(),
+        };
+        let debug = format!("{session:?}");
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("awiteb"));
+    }
+}
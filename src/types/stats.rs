@@ -0,0 +1,278 @@
+use super::{Todo, TodoStatus, User, PAGE_SIZE};
+use crate::errors::Result as OxideResult;
+use futures_util::future;
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::{Future, IntoFuture},
+    pin::Pin,
+    time::{Duration, SystemTime},
+};
+
+/// Seconds in a day.
+const DAY: u64 = 24 * 60 * 60;
+/// All the statuses, in the order of the counts.
+const STATUSES: [TodoStatus; 4] = [
+    TodoStatus::Pending,
+    TodoStatus::Progress,
+    TodoStatus::Completed,
+    TodoStatus::Cancelled,
+];
+
+/// The summary statistics of the user todos, returned by [`User::stats`].
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct TodoStats {
+    /// The amount of the todos by status, the statuses known by the SDK.
+    pub(crate) by_status: HashMap<TodoStatus, usize>,
+    /// The amount of the todos with a status which is not known by the SDK.
+    pub(crate) other: usize,
+    /// The average age of the open todos. (pending and in progress)
+    pub(crate) average_open_age: Option<Duration>,
+    /// The amount of the created todos per day, by days since the Unix epoch.
+    pub(crate) created_per_day: BTreeMap<u64, usize>,
+    /// The amount of the updated todos per day, by days since the Unix epoch.
+    pub(crate) updated_per_day: BTreeMap<u64, usize>,
+}
+
+/// A request of the user todos statistics, returned by [`User::stats`]. Await it to compute the statistics.
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[must_use]
+pub struct StatsRequest {
+    /// The user of the todos.
+    pub(crate) user: User,
+    /// `true` to compute the per day histograms, which need all the todos. (default: `false`)
+    pub(crate) histograms: bool,
+}
+
+impl TodoStats {
+    /// Returns the amount of all the todos, including the todos with an unknown status.
+    pub fn total(&self) -> usize {
+        self.by_status.values().sum::<usize>() + self.other
+    }
+
+    /// Returns the amount of the todos with the given status.
+    /// The todos with an unknown status are counted in [`TodoStats::other`], so it's `0` for [`TodoStatus::Unknown`].
+    pub fn count(&self, status: &TodoStatus) -> usize {
+        self.by_status.get(status).copied().unwrap_or_default()
+    }
+
+    /// Returns the amount of the todos with a status which is not known by the SDK. See [`TodoStatus::Unknown`].
+    pub fn other(&self) -> usize {
+        self.other
+    }
+
+    /// Returns the amount of the todos by status, the statuses known by the SDK.
+    pub fn by_status(&self) -> &HashMap<TodoStatus, usize> {
+        &self.by_status
+    }
+
+    /// Returns the completion rate, the completed todos of the todos which are not cancelled, from `0.0` to `1.0`.
+    /// `None` if all the todos are cancelled or there are no todos.
+    pub fn completion_rate(&self) -> Option<f64> {
+        let total = self.total() - self.count(&TodoStatus::Cancelled);
+        (total != 0).then(|| self.count(&TodoStatus::Completed) as f64 / total as f64)
+    }
+
+    /// Returns the average age of the open todos (pending and in progress), `None` if there are no open todos.
+    pub fn average_open_age(&self) -> Option<Duration> {
+        self.average_open_age
+    }
+
+    /// Returns the amount of the created todos per day, by days since the Unix epoch. (UTC)
+    /// The day `n` starts at the Unix timestamp `n * 86400`. Empty if the histograms are disabled.
+    pub fn created_per_day(&self) -> &BTreeMap<u64, usize> {
+        &self.created_per_day
+    }
+
+    /// Returns the amount of the updated todos per day, by days since the Unix epoch. (UTC)
+    /// The day `n` starts at the Unix timestamp `n * 86400`. Empty if the histograms are disabled.
+    pub fn updated_per_day(&self) -> &BTreeMap<u64, usize> {
+        &self.updated_per_day
+    }
+
+    /// Compute the statistics from all the todos.
+    fn from_todos(todos: &[Todo]) -> Self {
+        let mut stats = Self {
+            average_open_age: average_open_age(todos),
+            ..Self::default()
+        };
+        for todo in todos {
+            match &todo.status {
+                TodoStatus::Unknown(_) => stats.other += 1,
+                status => *stats.by_status.entry(status.clone()).or_default() += 1,
+            }
+            *stats
+                .created_per_day
+                .entry(todo.created_at / DAY)
+                .or_default() += 1;
+            *stats
+                .updated_per_day
+                .entry(todo.updated_at / DAY)
+                .or_default() += 1;
+        }
+        stats
+    }
+}
+
+impl StatsRequest {
+    /// Compute the per day histograms. (default: `false`)
+    ///
+    /// The histograms need all the todos, without them the counts are read from the server totals
+    /// and only the open todos are fetched, for their average age.
+    pub fn histograms(mut self, histograms: bool) -> Self {
+        self.histograms = histograms;
+        self
+    }
+
+    /// Compute the statistics.
+    ///
+    /// Without the histograms, the counts are read with one request of one todo per status and one for all the
+    /// todos, the todos which are not counted by a status have an unknown status. It falls back to a full scan
+    /// if the server doesn't return the totals, or if the totals of the statuses are more than the total.
+    pub async fn compute(self) -> OxideResult<TodoStats> {
+        if !self.histograms {
+            let totals = STATUSES
+                .iter()
+                .map(|status| self.user.todos().status(status.clone()).server_total());
+            let (totals, total) = future::try_join(
+                future::try_join_all(totals),
+                self.user.todos().server_total(),
+            )
+            .await?;
+            let totals: Option<Vec<usize>> = totals.into_iter().collect();
+            if let Some(other) = totals
+                .as_ref()
+                .zip(total)
+                .and_then(|(totals, total)| total.checked_sub(totals.iter().sum()))
+            {
+                let open = future::try_join_all(
+                    [TodoStatus::Pending, TodoStatus::Progress]
+                        .map(|status| self.user.todos().status(status).limit(PAGE_SIZE).all()),
+                )
+                .await?
+                .concat();
+                return Ok(TodoStats {
+                    by_status: STATUSES
+                        .into_iter()
+                        .zip(totals.into_iter().flatten())
+                        .collect(),
+                    other,
+                    average_open_age: average_open_age(&open),
+                    ..TodoStats::default()
+                });
+            }
+        }
+        let todos = self.user.todos().limit(PAGE_SIZE).all().await?;
+        Ok(TodoStats::from_todos(&todos))
+    }
+}
+
+impl IntoFuture for StatsRequest {
+    type Output = OxideResult<TodoStats>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.compute())
+    }
+}
+
+impl User {
+    /// Returns a request of the todos statistics: the counts by status, the completion rate, the average age of
+    /// the open todos and optionally the created and updated per day histograms. Await it to compute them.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::TodoStatus;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     // Without the histograms (the default), the counts are read from the server totals
+    ///     let stats = user.stats().await?;
+    ///     println!("Pending: {}", stats.count(&TodoStatus::Pending));
+    ///     if let Some(rate) = stats.completion_rate() {
+    ///         println!("Completion rate: {:.0}%", rate * 100.0);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn stats(&self) -> StatsRequest {
+        StatsRequest {
+            user: self.clone(),
+            histograms: false,
+        }
+    }
+}
+
+/// Returns the average age of the open todos (pending and in progress), `None` if there are no open todos.
+fn average_open_age(todos: &[Todo]) -> Option<Duration> {
+    let now = SystemTime::now();
+    let ages: Vec<Duration> = todos
+        .iter()
+        .filter(|todo| matches!(todo.status, TodoStatus::Pending | TodoStatus::Progress))
+        .map(|todo| {
+            now.duration_since(todo.created_at_system())
+                .unwrap_or_default()
+        })
+        .collect();
+    (!ages.is_empty()).then(|| ages.iter().sum::<Duration>() / ages.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_todo;
+
+    fn todo(status: &str, created_at: u64, updated_at: u64) -> Todo {
+        test_todo("Todo", status, created_at, updated_at)
+    }
+
+    #[test]
+    fn counts_by_status() {
+        let stats = TodoStats::from_todos(&[
+            todo("pending", 0, 0),
+            todo("pending", 0, 0),
+            todo("completed", 0, 0),
+        ]);
+        assert_eq!(stats.count(&TodoStatus::Pending), 2);
+        assert_eq!(stats.count(&TodoStatus::Completed), 1);
+        assert_eq!(stats.count(&TodoStatus::Cancelled), 0);
+        assert_eq!(stats.total(), 3);
+    }
+
+    #[test]
+    fn unknown_statuses_are_counted_in_other() {
+        let stats = TodoStats::from_todos(&[
+            todo("pending", 0, 0),
+            todo("completed", 0, 0),
+            todo("archived", 0, 0),
+        ]);
+        assert_eq!(stats.count(&TodoStatus::Pending), 1);
+        assert_eq!(stats.count(&TodoStatus::Unknown("archived".to_owned())), 0);
+        assert_eq!(stats.other(), 1);
+        assert_eq!(stats.total(), 3);
+    }
+
+    #[test]
+    fn completion_rate_ignores_cancelled() {
+        let stats = TodoStats::from_todos(&[
+            todo("completed", 0, 0),
+            todo("pending", 0, 0),
+            todo("cancelled", 0, 0),
+        ]);
+        assert_eq!(stats.completion_rate(), Some(0.5));
+        assert_eq!(
+            TodoStats::from_todos(&[todo("cancelled", 0, 0)]).completion_rate(),
+            None
+        );
+    }
+
+    #[test]
+    fn histograms_are_by_day() {
+        let stats =
+            TodoStats::from_todos(&[todo("pending", 0, DAY), todo("pending", DAY - 1, DAY + 1)]);
+        assert_eq!(stats.created_per_day().get(&0), Some(&2));
+        assert_eq!(stats.updated_per_day().get(&1), Some(&2));
+    }
+}
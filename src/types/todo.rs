@@ -1,17 +1,26 @@
+use super::{TodoDiff, Workflow};
 use crate::{
-    api_helper::Endpoints,
-    errors::{Result as OxideResult, SDKError},
+    api_helper::{Endpoints, Timeouts},
+    cache::Cache,
+    errors::{Error, Result as OxideResult, SDKError, TodoConflict},
+    validation::TitleRules,
 };
 use std::{
+    convert::Infallible,
+    fmt,
     future::{Future, IntoFuture},
     pin::Pin,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use uuid::Uuid;
 
 /// The todo status.
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+///
+/// The status is (de)serialized as its lowercase name, a status which is not known by the SDK is kept as
+/// [`TodoStatus::Unknown`], so the todos with a new server status can still be loaded and sent back.
+#[derive(Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "debug", derive(Debug))]
-#[serde(rename_all = "lowercase")]
 pub enum TodoStatus {
     /// The todo are completed.
     Completed,
@@ -21,13 +30,16 @@ pub enum TodoStatus {
     Pending,
     /// The todo are cancelled.
     Cancelled,
+    /// A status which is not known by the SDK, with its name. For example a status added to the server later.
+    Unknown(String),
 }
 
-/// Todo type is flexible. You can await it directly. How its works?
-/// - If the todo you awaited it has a uuid, it will update the todo on the server if you set the title or status, else will get the todo from the server.
-/// - If the todo you awaited it has no uuid, it will create a new todo on the server.
+/// A todo fetched from the server.
 ///
-/// For example you want to create a new todo:
+/// All the fields of a fetched todo are known, so the getters don't return `Option`.
+/// To change the todo use [`Todo::set_title`], [`Todo::set_status`] or [`Todo::patch`], they will return a [`TodoPatch`]
+/// which you can send to the server with [`TodoPatch::update`] (or await it directly).
+/// ### Example
 /// ```rust |no_run
 /// use oxide_todo_sdk::Client;
 /// use oxide_todo_sdk::types::TodoStatus;
@@ -36,80 +48,371 @@ pub enum TodoStatus {
 /// #[tokio::main]
 /// async fn main() -> OxideResult<()> {
 ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
-///     let todo = user.create_todo("My new todo").set_status(TodoStatus::Progress).await?;
-///     Ok(())
+///     // Create a new todo
+///     let todo = user.create_todo("My new todo").set_status(TodoStatus::Pending).create().await?;
+///     // Update the todo
+///     let todo = todo.set_status(TodoStatus::Progress).update().await?;
+///     // Fetch the todo again from the server
+///     let todo = todo.fetch().await?;
+///     // Delete the todo
+///     todo.delete().await
 /// }
 /// ```
-/// Just like that. You can also update a todo by uuid:
-/// ```rust |no_run
-/// use oxide_todo_sdk::Client;
-/// use oxide_todo_sdk::types::TodoStatus;
-/// use oxide_todo_sdk::errors::Result as OxideResult;
-/// use uuid::Uuid;
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Todo {
+    /// The base url.
+    #[serde(skip)]
+    pub(crate) base_url: String,
+    #[serde(skip)]
+    /// The client token.
+    pub(crate) token: String,
+    /// The client cache.
+    #[serde(skip)]
+    pub(crate) cache: Option<Cache>,
+    /// The client timeouts.
+    #[serde(skip)]
+    pub(crate) timeouts: Timeouts,
+    /// The client workflow, `None` if the status transitions are not validated.
+    #[serde(skip)]
+    pub(crate) workflow: Option<Workflow>,
+    /// The client title rules.
+    #[serde(skip)]
+    pub(crate) title_rules: TitleRules,
+    /// The todo uuid.
+    pub(crate) uuid: Uuid,
+    /// The todo title.
+    pub(crate) title: String,
+    /// Todo creation time. (Unix timestamp in seconds)
+    pub(crate) created_at: u64,
+    /// Last todo update time. (Unix timestamp in seconds)
+    pub(crate) updated_at: u64,
+    /// The todo status.
+    pub(crate) status: TodoStatus,
+}
+
+/// A todo draft, which is not created on the server yet.
 ///
-/// #[tokio::main]
-/// async fn main() -> OxideResult<()> {
-///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
-///     let todo = user.todo_by_uuid(Uuid::new_v4()).set_status(TodoStatus::Progress).await?;
-///     Ok(())
-/// }
-/// ```
-/// And to get the todo, you will do same thing as above. just don't set the status or title.
+/// You can get it from [`TodoBuilder::build`], then create it on the server with [`NewTodo::create`] (or await it directly).
+///
+/// [`TodoBuilder::build`]: crate::types::TodoBuilder::build
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[must_use]
+pub struct NewTodo {
+    /// The base url.
+    pub(crate) base_url: String,
+    /// The client token.
+    pub(crate) token: String,
+    /// The client cache.
+    pub(crate) cache: Option<Cache>,
+    /// The client timeouts.
+    pub(crate) timeouts: Timeouts,
+    /// The client workflow, `None` if the status transitions are not validated.
+    pub(crate) workflow: Option<Workflow>,
+    /// The client title rules.
+    pub(crate) title_rules: TitleRules,
+    /// The todo title.
+    pub(crate) title: String,
+    /// The todo status.
+    pub(crate) status: TodoStatus,
+}
+
+/// A patch of an existing todo, only the fields you set will be updated on the server.
+///
+/// You can get it from [`User::update_todo`] or from a fetched [`Todo`], then send it to the server
+/// with [`TodoPatch::update`] (or await it directly).
+/// ### Example
 /// ```rust |no_run
 /// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::types::TodoStatus;
 /// use oxide_todo_sdk::errors::Result as OxideResult;
 /// use uuid::Uuid;
 ///
 /// #[tokio::main]
 /// async fn main() -> OxideResult<()> {
 ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
-///     let todo = user.todo_by_uuid(Uuid::new_v4()).await?;
+///     let todo = user.update_todo(Uuid::new_v4())
+///         .set_title("New title")
+///         .set_status(TodoStatus::Completed)
+///         .update()
+///         .await?;
 ///     Ok(())
 /// }
 /// ```
-/// You can also delete the todo by calling `Todo::delete`:
-/// ```rust |no_run
-/// use oxide_todo_sdk::Client;
-/// use oxide_todo_sdk::errors::Result as OxideResult;
 ///
-/// #[tokio::main]
-/// async fn main() -> OxideResult<()> {
-///     let todo = Client::new("http://localhost:8080")
-///         .login_by_token("YOUR_TOKEN")
-///         .create_todo("My new todo")
-///         .await?;
-///     todo.delete().await
-/// }
-/// ```
-/// Easy right?
-#[derive(serde::Deserialize, Default)]
+/// [`User::update_todo`]: crate::types::User::update_todo
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[must_use]
-pub struct Todo {
+pub struct TodoPatch {
     /// The base url.
-    #[serde(skip)]
     pub(crate) base_url: String,
-    #[serde(skip)]
     /// The client token.
     pub(crate) token: String,
-    /// The todo uuid.
-    pub(crate) uuid: Option<Uuid>,
-    /// The todo title.
+    /// The client cache.
+    pub(crate) cache: Option<Cache>,
+    /// The client timeouts.
+    pub(crate) timeouts: Timeouts,
+    /// The client workflow, `None` if the status transitions are not validated.
+    pub(crate) workflow: Option<Workflow>,
+    /// The client title rules.
+    pub(crate) title_rules: TitleRules,
+    /// The uuid of the todo to update.
+    pub(crate) uuid: Uuid,
+    /// The new title of the todo, `None` to keep the current title.
     pub(crate) title: Option<String>,
-    /// Todo creation time.
-    pub(crate) created_at: Option<u64>,
-    /// Last todo update time.
-    pub(crate) updated_at: Option<u64>,
-    /// The todo status.
+    /// The new status of the todo, `None` to keep the current status.
     pub(crate) status: Option<TodoStatus>,
+    /// The current status of the todo, `None` if it's not known.
+    pub(crate) current_status: Option<TodoStatus>,
 }
 
 impl Todo {
+    /// Deserialize the todo from the server response, and attach the base url and the token to it.
+    /// Returns an error if the response is not a todo.
+    pub(crate) fn from_value(
+        base_url: impl Into<String>,
+        token: impl Into<String>,
+        cache: Option<Cache>,
+        timeouts: Timeouts,
+        workflow: Option<Workflow>,
+        title_rules: TitleRules,
+        value: serde_json::Value,
+    ) -> OxideResult<Self> {
+        Ok(Self {
+            base_url: base_url.into(),
+            token: token.into(),
+            cache,
+            timeouts,
+            workflow,
+            title_rules,
+            ..serde_json::from_value(value)?
+        })
+    }
+
+    /// Returns the UUID of the todo.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Returns the title of the todo.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the status of the todo.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status
+    }
+
+    /// Returns the creation time of the todo, as a Unix timestamp in seconds.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Returns the last update time of the todo, as a Unix timestamp in seconds.
+    pub fn updated_at(&self) -> u64 {
+        self.updated_at
+    }
+
+    /// Returns the creation time of the todo as a [`SystemTime`].
+    pub fn created_at_system(&self) -> SystemTime {
+        system_time(self.created_at)
+    }
+
+    /// Returns the last update time of the todo as a [`SystemTime`].
+    pub fn updated_at_system(&self) -> SystemTime {
+        system_time(self.updated_at)
+    }
+
+    /// Returns the creation time of the todo as a [`chrono::DateTime`]. (Requires the `chrono` feature)
+    #[cfg(feature = "chrono")]
+    pub fn created_at_chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        self.created_at_system().into()
+    }
+
+    /// Returns the last update time of the todo as a [`chrono::DateTime`]. (Requires the `chrono` feature)
+    #[cfg(feature = "chrono")]
+    pub fn updated_at_chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        self.updated_at_system().into()
+    }
+
+    /// Returns the creation time of the todo as a [`time::OffsetDateTime`]. (Requires the `time` feature)
+    #[cfg(feature = "time")]
+    pub fn created_at_time(&self) -> time::OffsetDateTime {
+        self.created_at_system().into()
+    }
+
+    /// Returns the last update time of the todo as a [`time::OffsetDateTime`]. (Requires the `time` feature)
+    #[cfg(feature = "time")]
+    pub fn updated_at_time(&self) -> time::OffsetDateTime {
+        self.updated_at_system().into()
+    }
+
+    /// Returns an empty patch of the todo. Set the fields you want to update then send it with [`TodoPatch::update`].
+    pub fn patch(&self) -> TodoPatch {
+        TodoPatch {
+            base_url: self.base_url.clone(),
+            token: self.token.clone(),
+            cache: self.cache.clone(),
+            timeouts: self.timeouts,
+            workflow: self.workflow.clone(),
+            title_rules: self.title_rules,
+            uuid: self.uuid,
+            title: None,
+            status: None,
+            current_status: Some(self.status.clone()),
+        }
+    }
+
+    /// Returns a patch of the todo with the new title.
+    pub fn set_title(&self, title: impl Into<String>) -> TodoPatch {
+        self.patch().set_title(title)
+    }
+
+    /// Returns a patch of the todo with the new status.
+    pub fn set_status(&self, status: TodoStatus) -> TodoPatch {
+        self.patch().set_status(status)
+    }
+
+    /// Start the todo, update its status to [`TodoStatus::Progress`]. The transition is validated by the client workflow.
+    pub async fn start(&self) -> OxideResult<Todo> {
+        self.set_status(TodoStatus::Progress).update().await
+    }
+
+    /// Complete the todo, update its status to [`TodoStatus::Completed`]. The transition is validated by the client workflow.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::{TodoStatus, Workflow};
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080")
+    ///         .with_workflow(Workflow::default())
+    ///         .login_by_token("YOUR_TOKEN");
+    ///     let todo = user.create_todo("My new todo").set_status(TodoStatus::Pending).await?;
+    ///     let todo = todo.start().await?.complete().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn complete(&self) -> OxideResult<Todo> {
+        self.set_status(TodoStatus::Completed).update().await
+    }
+
+    /// Cancel the todo, update its status to [`TodoStatus::Cancelled`]. The transition is validated by the client workflow.
+    pub async fn cancel(&self) -> OxideResult<Todo> {
+        self.set_status(TodoStatus::Cancelled).update().await
+    }
+
+    /// Reopen the todo, update its status to [`TodoStatus::Pending`]. The transition is validated by the client workflow.
+    pub async fn reopen(&self) -> OxideResult<Todo> {
+        self.set_status(TodoStatus::Pending).update().await
+    }
+
+    /// Fetch the todo again from the server. This will return the latest version of the todo.
+    ///
+    /// The todo is always fetched from the server, the client cache is not read.
+    pub async fn fetch(&self) -> OxideResult<Todo> {
+        Endpoints::GetTodo {
+            base_url: &self.base_url,
+            token: &self.token,
+            uuid: &self.uuid,
+        }
+        .send(self.timeouts)
+        .await
+        .and_then(|v| {
+            Todo::from_value(
+                &self.base_url,
+                &self.token,
+                self.cache.clone(),
+                self.timeouts,
+                self.workflow.clone(),
+                self.title_rules,
+                v,
+            )
+        })
+    }
+
+    /// Refresh the todo in place, with the latest version from the server.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    /// use uuid::Uuid;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let mut todo = user.todo_by_uuid(Uuid::new_v4()).await?;
+    ///     let old = todo.clone();
+    ///     todo.refresh().await?;
+    ///     for change in old.diff(&todo) {
+    ///         // Do something with the changed fields
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn refresh(&mut self) -> OxideResult<()> {
+        *self = self.fetch().await?;
+        Ok(())
+    }
+
+    /// Returns the changed fields from this todo to the other todo. (`self` is the old version)
+    pub fn diff(&self, other: &Todo) -> TodoDiff {
+        TodoDiff::new(self, other)
+    }
+
+    /// Send the patch to the server only if the todo has not been updated on the server since it was fetched.
+    ///
+    /// The server has no conditional update, so this will fetch the todo first and compare its `updated_at`
+    /// with this todo, if they are different it will return [`Error::Conflict`] with the local and the server versions.
+    /// ### Note
+    /// The todo can still be updated by another client between the fetch and the update.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::TodoStatus;
+    /// use oxide_todo_sdk::errors::{Error, Result as OxideResult};
+    /// use uuid::Uuid;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let todo = user.todo_by_uuid(Uuid::new_v4()).await?;
+    ///     match todo.update_if_unchanged(todo.set_status(TodoStatus::Completed)).await {
+    ///         Ok(todo) => println!("Updated: {}", todo.title()),
+    ///         Err(Error::Conflict(conflict)) => println!("Updated by someone else: {}", conflict.server().title()),
+    ///         Err(err) => return Err(err),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Error::Conflict`]: crate::errors::Error::Conflict
+    pub async fn update_if_unchanged(&self, patch: TodoPatch) -> OxideResult<Todo> {
+        if patch.uuid != self.uuid {
+            return Err(SDKError::UuidMismatch {
+                expected: self.uuid,
+                found: patch.uuid,
+            }
+            .into());
+        }
+        let server = self.fetch().await?;
+        if server.updated_at != self.updated_at {
+            return Err(Error::Conflict(Box::new(TodoConflict {
+                local: self.clone(),
+                server,
+            })));
+        }
+        patch.update().await
+    }
+
     /// Delete the todo. This will delete the todo from the server.
-    /// If the todo has no uuid, it will return an error.
     /// ### Example
     /// ```rust |no_run
     /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::TodoStatus;
     /// use oxide_todo_sdk::errors::Result as OxideResult;
     ///
     /// #[tokio::main]
@@ -118,31 +421,82 @@ impl Todo {
     ///     let todo = Client::new("http://localhost:8080")
     ///         .login_by_token("YOUR_TOKEN")
     ///         .create_todo("My new todo")
+    ///         .set_status(TodoStatus::Pending)
     ///         .await?;
     ///     // Delete todo
     ///     todo.delete().await
     /// }
     /// ```
     pub async fn delete(self) -> OxideResult<()> {
-        if let Some(uuid) = self.uuid {
-            Endpoints::DeleteTodo {
-                base_url: &self.base_url,
-                token: &self.token,
-                uuid: &uuid,
-            }
-            .await
-            .map(|_| ())
-        } else {
-            Err(SDKError::MissingField("`uuid` is required to delete a todo.".to_owned()).into())
+        Endpoints::DeleteTodo {
+            base_url: &self.base_url,
+            token: &self.token,
+            uuid: &self.uuid,
         }
+        .send_cached(self.cache.as_ref(), self.timeouts)
+        .await
+        .map(|_| ())
+    }
+}
+
+impl NewTodo {
+    /// Set the title of the todo.
+    pub fn set_title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..self
+        }
+    }
+
+    /// Returns the title of the todo.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Set the status of the todo.
+    pub fn set_status(self, status: TodoStatus) -> Self {
+        Self { status, ..self }
     }
 
-    /// Returns a UUID of the todo, if the todo is created. Else it will return `None`.
-    pub fn uuid(&self) -> Option<Uuid> {
+    /// Returns the status of the todo.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status
+    }
+
+    /// Create the todo on the server, and returns the created todo.
+    ///
+    /// The title is validated by the client [`TitleRules`] first, an invalid title returns [`SDKError::Validation`].
+    pub async fn create(self) -> OxideResult<Todo> {
+        let title = self.title_rules.validate(&self.title)?;
+        Endpoints::CreateTodo {
+            base_url: &self.base_url,
+            token: &self.token,
+            title: &title,
+            status: self.status,
+        }
+        .send_cached(self.cache.as_ref(), self.timeouts)
+        .await
+        .and_then(|v| {
+            Todo::from_value(
+                self.base_url,
+                self.token,
+                self.cache,
+                self.timeouts,
+                self.workflow,
+                self.title_rules,
+                v,
+            )
+        })
+    }
+}
+
+impl TodoPatch {
+    /// Returns the uuid of the todo that will be updated.
+    pub fn uuid(&self) -> Uuid {
         self.uuid
     }
 
-    /// Set the title of the todo.
+    /// Set the new title of the todo.
     pub fn set_title(self, title: impl Into<String>) -> Self {
         Self {
             title: Some(title.into()),
@@ -150,12 +504,12 @@ impl Todo {
         }
     }
 
-    /// Returns the title of the todo.
-    pub fn title(&self) -> Option<&String> {
-        self.title.as_ref()
+    /// Returns the new title of the todo, if it's set.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
     }
 
-    /// Set the status of the todo.
+    /// Set the new status of the todo.
     pub fn set_status(self, status: TodoStatus) -> Self {
         Self {
             status: Some(status),
@@ -163,96 +517,199 @@ impl Todo {
         }
     }
 
-    /// Returns the status of the todo.
+    /// Returns the new status of the todo, if it's set.
     pub fn status(&self) -> Option<&TodoStatus> {
         self.status.as_ref()
     }
 
-    /// Returns the creation time of the todo.
-    pub fn created_at(&self) -> Option<u64> {
-        self.created_at
+    /// Send the patch to the server, and returns the updated todo.
+    ///
+    /// If the client has a [`Workflow`], the status transition is validated first and a forbidden transition returns
+    /// [`SDKError::InvalidTransition`]. The current status is fetched from the server if the patch is not from a todo.
+    /// The new title is validated by the client [`TitleRules`], an invalid title returns [`SDKError::Validation`].
+    pub async fn update(self) -> OxideResult<Todo> {
+        let title = self
+            .title
+            .as_deref()
+            .map(|title| self.title_rules.validate(title))
+            .transpose()?;
+        if let (Some(workflow), Some(status)) = (&self.workflow, &self.status) {
+            let current = match &self.current_status {
+                Some(current) => current.clone(),
+                None => serde_json::from_value(
+                    Endpoints::GetTodo {
+                        base_url: &self.base_url,
+                        token: &self.token,
+                        uuid: &self.uuid,
+                    }
+                    .send(self.timeouts)
+                    .await?["status"]
+                        .clone(),
+                )?,
+            };
+            workflow
+                .validate(&current, status)
+                .map_err(SDKError::from)?;
+        }
+        Endpoints::UpdateTodo {
+            base_url: &self.base_url,
+            token: &self.token,
+            uuid: &self.uuid,
+            title: title.as_deref(),
+            status: self.status,
+        }
+        .send_cached(self.cache.as_ref(), self.timeouts)
+        .await
+        .and_then(|v| {
+            Todo::from_value(
+                self.base_url,
+                self.token,
+                self.cache,
+                self.timeouts,
+                self.workflow,
+                self.title_rules,
+                v,
+            )
+        })
     }
+}
 
-    /// Returns the last update time of the todo.
-    pub fn updated_at(&self) -> Option<u64> {
-        self.updated_at
-    }
+/// Convert a Unix timestamp in seconds to a [`SystemTime`].
+fn system_time(timestamp: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(timestamp)
 }
 
-impl ToString for TodoStatus {
-    fn to_string(&self) -> String {
+impl TodoStatus {
+    /// Returns the name of the status, as it's sent to the server.
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Cancelled => "cancelled",
             Self::Completed => "completed",
             Self::Progress => "progress",
             Self::Pending => "pending",
+            Self::Unknown(status) => status,
         }
-        .to_owned()
+    }
+
+    /// Returns `true` if the status is known by the SDK.
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+
+    /// Parse the status name, a name which is not known by the SDK is [`TodoStatus::Unknown`].
+    /// Same as [`FromStr`], without the [`Infallible`] error.
+    pub(crate) fn from_name(status: &str) -> Self {
+        Self::from_str(status).unwrap_or_else(|never| match never {})
     }
 }
 
-impl IntoFuture for Todo {
+impl fmt::Display for TodoStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TodoStatus {
+    type Err = Infallible;
+
+    /// Parse the status name, a name which is not known by the SDK is [`TodoStatus::Unknown`].
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        Ok(match status {
+            "cancelled" => Self::Cancelled,
+            "completed" => Self::Completed,
+            "progress" => Self::Progress,
+            "pending" => Self::Pending,
+            status => Self::Unknown(status.to_owned()),
+        })
+    }
+}
+
+impl serde::Serialize for TodoStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TodoStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(Self::from_name(&status))
+    }
+}
+
+impl IntoFuture for NewTodo {
     type Output = OxideResult<Todo>;
     type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
 
     fn into_future(self) -> Self::IntoFuture {
-        Box::pin(async move {
-            // There is tow scenarios here:
-            // 1. The todo is created, and we want to update it.
-            // 2. The todo is not created, and we want to create it.
-            // 3. The todo is created, and we want to get it.
-            // We can know if the todo is created by checking if thay have a uuid or not.
-            if let Some(uuid) = self.uuid {
-                // The todo is created, we want to update it.
-                // Also maybe the user want to get the todo, so we need to check if all fields are None or not.
-                if self.status.is_none() && self.title.is_none() {
-                    // The user want to get the todo.
-                    Endpoints::GetTodo {
-                        base_url: &self.base_url,
-                        token: &self.token,
-                        uuid: &uuid,
-                    }
-                    .await
-                    .map(|v| Todo {
-                        base_url: self.base_url,
-                        token: self.token,
-                        ..serde_json::from_value(v).unwrap()
-                    })
-                } else {
-                    // The user want to update the todo.
-                    Endpoints::UpdateTodo {
-                        base_url: &self.base_url,
-                        token: &self.token,
-                        uuid: &uuid,
-                        title: self.title.as_deref(),
-                        status: self.status,
-                    }
-                    .await
-                    .map(|v| Todo {
-                        base_url: self.base_url,
-                        token: self.token,
-                        ..serde_json::from_value(v).unwrap()
-                    })
-                }
-            } else {
-                // The todo is not created, we want to create it.
-                Endpoints::CreateTodo {
-                    base_url: &self.base_url,
-                    token: &self.token,
-                    title: &self.title.ok_or_else(|| SDKError::missing_field("`title` needed to create a todo"))?,
-                    status: self
-                        .status
-                        .ok_or_else(|| SDKError::missing_field(
-                                "`status` you cannot create a todo without a status, use `Todo::set_status` to set the status"
-                            ))?,
-                }
-                .await
-                .map(|v| Todo {
-                    base_url: self.base_url,
-                    token: self.token,
-                    ..serde_json::from_value(v).unwrap()
-                })
-            }
-        })
+        Box::pin(self.create())
+    }
+}
+
+impl IntoFuture for TodoPatch {
+    type Output = OxideResult<Todo>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.update())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn statuses() -> [TodoStatus; 5] {
+        [
+            TodoStatus::Completed,
+            TodoStatus::Progress,
+            TodoStatus::Pending,
+            TodoStatus::Cancelled,
+            TodoStatus::Unknown("archived".to_owned()),
+        ]
+    }
+
+    #[test]
+    fn status_from_str_round_trip() {
+        for status in statuses() {
+            let parsed: TodoStatus = status.to_string().parse().unwrap();
+            assert!(parsed == status);
+        }
+    }
+
+    #[test]
+    fn status_serde_round_trip() {
+        for status in statuses() {
+            let value = serde_json::to_value(&status).unwrap();
+            assert_eq!(value, json!(status.as_str()));
+            let deserialized: TodoStatus = serde_json::from_value(value).unwrap();
+            assert!(deserialized == status);
+        }
+    }
+
+    #[test]
+    fn unknown_status_string() {
+        let status: TodoStatus = serde_json::from_value(json!("Completed")).unwrap();
+        assert!(status == TodoStatus::Unknown("Completed".to_owned()));
+        assert!(!status.is_known());
+        assert!(TodoStatus::Pending.is_known());
+        let status: TodoStatus = "".parse().unwrap();
+        assert!(status == TodoStatus::Unknown(String::new()));
+        assert!(serde_json::from_value::<TodoStatus>(json!(1)).is_err());
+    }
+
+    #[test]
+    fn todo_with_unknown_status_round_trip() {
+        let value = json!({
+            "uuid": Uuid::nil(),
+            "title": "Old",
+            "status": "archived",
+            "created_at": 1,
+            "updated_at": 2,
+        });
+        let todo: Todo = serde_json::from_value(value).unwrap();
+        assert!(todo.status() == &TodoStatus::Unknown("archived".to_owned()));
+        assert_eq!(serde_json::to_value(&todo).unwrap()["status"], "archived");
     }
 }
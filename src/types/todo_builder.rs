@@ -0,0 +1,133 @@
+use super::{NewTodo, Todo, TodoStatus, Workflow};
+use crate::{
+    api_helper::Timeouts, cache::Cache, errors::Result as OxideResult, validation::TitleRules,
+};
+use std::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+};
+
+/// The status state of a [`TodoBuilder`] without a status. You can't create the todo in this state.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct NoStatus;
+
+/// The status state of a [`TodoBuilder`] with a status. You can create the todo in this state.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct HasStatus(pub(crate) TodoStatus);
+
+/// A todo builder. It's make sure that the todo has a title and a status before creating it, at compile time.
+///
+/// You can get it from [`User::create_todo`], it will be in the [`NoStatus`] state, and after you set the status
+/// with [`TodoBuilder::set_status`] it will be in the [`HasStatus`] state, and then you can create the todo
+/// with [`TodoBuilder::create`] (or await it directly).
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::types::TodoStatus;
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+///     let todo = user.create_todo("My new todo") // Type: TodoBuilder<NoStatus>
+///         .set_status(TodoStatus::Pending) // Type: TodoBuilder<HasStatus>
+///         .await?; // Type: Todo
+///     // This will not compile, because the status is not set
+///     // let todo = user.create_todo("My new todo").await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`User::create_todo`]: crate::types::User::create_todo
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[must_use]
+pub struct TodoBuilder<S> {
+    /// The base url.
+    pub(crate) base_url: String,
+    /// The client token.
+    pub(crate) token: String,
+    /// The client cache.
+    pub(crate) cache: Option<Cache>,
+    /// The client timeouts.
+    pub(crate) timeouts: Timeouts,
+    /// The client workflow, `None` if the status transitions are not validated.
+    pub(crate) workflow: Option<Workflow>,
+    /// The client title rules.
+    pub(crate) title_rules: TitleRules,
+    /// The todo title.
+    pub(crate) title: String,
+    /// The status state of the builder.
+    pub(crate) status: S,
+}
+
+impl<S> TodoBuilder<S> {
+    /// Set the title of the todo.
+    pub fn set_title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..self
+        }
+    }
+
+    /// Returns the title of the todo.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Set the status of the todo. This will move the builder to the [`HasStatus`] state.
+    pub fn set_status(self, status: TodoStatus) -> TodoBuilder<HasStatus> {
+        TodoBuilder {
+            base_url: self.base_url,
+            token: self.token,
+            cache: self.cache,
+            timeouts: self.timeouts,
+            workflow: self.workflow,
+            title_rules: self.title_rules,
+            title: self.title,
+            status: HasStatus(status),
+        }
+    }
+}
+
+impl TodoBuilder<HasStatus> {
+    /// Returns the status of the todo.
+    pub fn status(&self) -> &TodoStatus {
+        &self.status.0
+    }
+
+    /// Build the todo draft, without creating it on the server.
+    ///
+    /// The status must be set first, building a [`TodoBuilder<NoStatus>`] doesn't compile:
+    /// ```compile_fail
+    /// use oxide_todo_sdk::Client;
+    ///
+    /// let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    /// let todo = user.create_todo("My new todo").build(); // The status is not set
+    /// ```
+    pub fn build(self) -> NewTodo {
+        NewTodo {
+            base_url: self.base_url,
+            token: self.token,
+            cache: self.cache,
+            timeouts: self.timeouts,
+            workflow: self.workflow,
+            title_rules: self.title_rules,
+            title: self.title,
+            status: self.status.0,
+        }
+    }
+
+    /// Create the todo on the server, and returns the created todo.
+    pub async fn create(self) -> OxideResult<Todo> {
+        self.build().create().await
+    }
+}
+
+impl IntoFuture for TodoBuilder<HasStatus> {
+    type Output = OxideResult<Todo>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.create())
+    }
+}
@@ -0,0 +1,123 @@
+use super::{Todo, TodoStatus};
+use uuid::Uuid;
+
+/// A changed field between two versions of a todo. See [`Todo::diff`].
+#[derive(Clone, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum TodoChange {
+    /// The uuid has been changed. (The todos are not the same todo)
+    Uuid {
+        /// The old uuid.
+        old: Uuid,
+        /// The new uuid.
+        new: Uuid,
+    },
+    /// The title has been changed.
+    Title {
+        /// The old title.
+        old: String,
+        /// The new title.
+        new: String,
+    },
+    /// The status has been changed.
+    Status {
+        /// The old status.
+        old: TodoStatus,
+        /// The new status.
+        new: TodoStatus,
+    },
+    /// The creation time has been changed.
+    CreatedAt {
+        /// The old creation time. (Unix timestamp in seconds)
+        old: u64,
+        /// The new creation time. (Unix timestamp in seconds)
+        new: u64,
+    },
+    /// The last update time has been changed.
+    UpdatedAt {
+        /// The old last update time. (Unix timestamp in seconds)
+        old: u64,
+        /// The new last update time. (Unix timestamp in seconds)
+        new: u64,
+    },
+}
+
+/// The difference between two versions of a todo, it's a list of the changed fields. See [`Todo::diff`].
+#[derive(Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct TodoDiff {
+    /// The changed fields.
+    pub(crate) changes: Vec<TodoChange>,
+}
+
+impl TodoDiff {
+    /// Returns the difference between the old and the new version of the todo.
+    pub(crate) fn new(old: &Todo, new: &Todo) -> Self {
+        let mut changes = Vec::new();
+        if old.uuid != new.uuid {
+            changes.push(TodoChange::Uuid {
+                old: old.uuid,
+                new: new.uuid,
+            });
+        }
+        if old.title != new.title {
+            changes.push(TodoChange::Title {
+                old: old.title.clone(),
+                new: new.title.clone(),
+            });
+        }
+        if old.status != new.status {
+            changes.push(TodoChange::Status {
+                old: old.status.clone(),
+                new: new.status.clone(),
+            });
+        }
+        if old.created_at != new.created_at {
+            changes.push(TodoChange::CreatedAt {
+                old: old.created_at,
+                new: new.created_at,
+            });
+        }
+        if old.updated_at != new.updated_at {
+            changes.push(TodoChange::UpdatedAt {
+                old: old.updated_at,
+                new: new.updated_at,
+            });
+        }
+        Self { changes }
+    }
+
+    /// Returns `true` if there is no changed field.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the changed fields.
+    pub fn changes(&self) -> &[TodoChange] {
+        &self.changes
+    }
+
+    /// Returns `true` if the title has been changed.
+    pub fn title_changed(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, TodoChange::Title { .. }))
+    }
+
+    /// Returns `true` if the status has been changed.
+    pub fn status_changed(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, TodoChange::Status { .. }))
+    }
+}
+
+impl IntoIterator for TodoDiff {
+    type Item = TodoChange;
+    type IntoIter = std::vec::IntoIter<TodoChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
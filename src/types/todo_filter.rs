@@ -0,0 +1,318 @@
+use super::{Todo, TodoStatus, Todos};
+use std::{
+    ops::{BitAnd, BitOr, Bound, Not, RangeBounds},
+    time::SystemTime,
+};
+
+/// A filter expression of the todos, evaluated locally on the fetched todos.
+///
+/// The filters can be combined with [`TodoFilter::and`] and [`TodoFilter::or`] (or `&` and `|`), and negated with `!`.
+/// Use it with [`Todos::filter`], the parts that the server supports (the status and the title) are sent to the server
+/// and the rest is evaluated locally while paging, so the limit and the offset are of the filtered todos.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::types::{TodoFilter, TodoStatus};
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+/// use std::time::{Duration, SystemTime};
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+///     let last_week = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
+///     // The open todos of the last week, which are not about homeworks
+///     let filter = TodoFilter::status_in([TodoStatus::Pending, TodoStatus::Progress])
+///         & TodoFilter::created_between(last_week..)
+///         & !TodoFilter::title_contains("Homework");
+///     let todos = user.todos().filter(filter).limit(20).await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum TodoFilter {
+    /// The todo status is the given status.
+    Status(TodoStatus),
+    /// The todo status is one of the given statuses.
+    StatusIn(Vec<TodoStatus>),
+    /// The todo title contains the given text.
+    TitleContains(String),
+    /// The todo title matches the given regex. (Requires the `regex` feature)
+    #[cfg(feature = "regex")]
+    TitleMatches(regex::Regex),
+    /// The todo creation time is in the given range.
+    CreatedBetween(Bound<SystemTime>, Bound<SystemTime>),
+    /// The todo last update time is in the given range.
+    UpdatedBetween(Bound<SystemTime>, Bound<SystemTime>),
+    /// All the filters match the todo. (An empty list matches all the todos)
+    And(Vec<TodoFilter>),
+    /// Any of the filters matches the todo. (An empty list matches no todo)
+    Or(Vec<TodoFilter>),
+    /// The filter doesn't match the todo.
+    Not(Box<TodoFilter>),
+}
+
+impl TodoFilter {
+    /// Returns a filter of the todos with the given status.
+    pub fn status(status: TodoStatus) -> Self {
+        Self::Status(status)
+    }
+
+    /// Returns a filter of the todos with one of the given statuses.
+    pub fn status_in(statuses: impl IntoIterator<Item = TodoStatus>) -> Self {
+        Self::StatusIn(statuses.into_iter().collect())
+    }
+
+    /// Returns a filter of the todos which their title contains the given text.
+    pub fn title_contains(text: impl Into<String>) -> Self {
+        Self::TitleContains(text.into())
+    }
+
+    /// Returns a filter of the todos which their title matches the given regex. (Requires the `regex` feature)
+    #[cfg(feature = "regex")]
+    pub fn title_matches(regex: regex::Regex) -> Self {
+        Self::TitleMatches(regex)
+    }
+
+    /// Returns a filter of the todos created in the given range, e.g. `start..end` or `start..`.
+    pub fn created_between(range: impl RangeBounds<SystemTime>) -> Self {
+        Self::CreatedBetween(range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    /// Returns a filter of the todos last updated in the given range, e.g. `start..end` or `start..`.
+    pub fn updated_between(range: impl RangeBounds<SystemTime>) -> Self {
+        Self::UpdatedBetween(range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    /// Returns a filter which matches the todos matched by both filters.
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::And(mut filters), Self::And(others)) => {
+                filters.extend(others);
+                Self::And(filters)
+            }
+            (Self::And(mut filters), other) => {
+                filters.push(other);
+                Self::And(filters)
+            }
+            (filter, Self::And(mut filters)) => {
+                filters.insert(0, filter);
+                Self::And(filters)
+            }
+            (filter, other) => Self::And(vec![filter, other]),
+        }
+    }
+
+    /// Returns a filter which matches the todos matched by any of the filters.
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Or(mut filters), Self::Or(others)) => {
+                filters.extend(others);
+                Self::Or(filters)
+            }
+            (Self::Or(mut filters), other) => {
+                filters.push(other);
+                Self::Or(filters)
+            }
+            (filter, Self::Or(mut filters)) => {
+                filters.insert(0, filter);
+                Self::Or(filters)
+            }
+            (filter, other) => Self::Or(vec![filter, other]),
+        }
+    }
+
+    /// Returns `true` if the filter matches the todo.
+    pub fn matches(&self, todo: &Todo) -> bool {
+        match self {
+            Self::Status(status) => &todo.status == status,
+            Self::StatusIn(statuses) => statuses.contains(&todo.status),
+            Self::TitleContains(text) => todo.title.contains(text.as_str()),
+            #[cfg(feature = "regex")]
+            Self::TitleMatches(regex) => regex.is_match(&todo.title),
+            Self::CreatedBetween(start, end) => in_range(start, end, todo.created_at_system()),
+            Self::UpdatedBetween(start, end) => in_range(start, end, todo.updated_at_system()),
+            Self::And(filters) => filters.iter().all(|filter| filter.matches(todo)),
+            Self::Or(filters) => filters.iter().any(|filter| filter.matches(todo)),
+            Self::Not(filter) => !filter.matches(todo),
+        }
+    }
+
+    /// Returns the status which all the matched todos have, if there is one. The server can filter by it.
+    fn required_status(&self) -> Option<&TodoStatus> {
+        match self {
+            Self::Status(status) => Some(status),
+            Self::StatusIn(statuses) if statuses.len() == 1 => statuses.first(),
+            Self::And(filters) => filters.iter().find_map(Self::required_status),
+            _ => None,
+        }
+    }
+
+    /// Returns a text which all the matched todos titles contain, if there is one. The server can filter by it.
+    fn required_title(&self) -> Option<&str> {
+        match self {
+            Self::TitleContains(text) => Some(text),
+            Self::And(filters) => filters.iter().find_map(Self::required_title),
+            _ => None,
+        }
+    }
+
+    /// Push the parts of the filter that the server supports down to the server filters of the todos.
+    /// The server returns a superset of the matched todos, the filter still has to be evaluated locally.
+    pub(crate) fn push_down(&self, mut todos: Todos) -> Todos {
+        if todos.status.is_none() && todos.statuses.is_empty() {
+            todos.status = self.required_status().cloned();
+        }
+        if todos.title.is_none() {
+            todos.title = self.required_title().map(ToOwned::to_owned);
+        }
+        todos
+    }
+}
+
+impl BitAnd for TodoFilter {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.and(other)
+    }
+}
+
+impl BitOr for TodoFilter {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.or(other)
+    }
+}
+
+impl Not for TodoFilter {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        match self {
+            Self::Not(filter) => *filter,
+            filter => Self::Not(Box::new(filter)),
+        }
+    }
+}
+
+/// Returns `true` if the time is in the range of the bounds.
+fn in_range(start: &Bound<SystemTime>, end: &Bound<SystemTime>, time: SystemTime) -> bool {
+    let after_start = match start {
+        Bound::Included(start) => &time >= start,
+        Bound::Excluded(start) => &time > start,
+        Bound::Unbounded => true,
+    };
+    let before_end = match end {
+        Bound::Included(end) => &time <= end,
+        Bound::Excluded(end) => &time < end,
+        Bound::Unbounded => true,
+    };
+    after_start && before_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_todo;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn todo(title: &str, status: &str, created_at: u64) -> Todo {
+        test_todo(title, status, created_at, created_at + 10)
+    }
+
+    fn new_todos() -> Todos {
+        Todos::new(
+            "http://localhost:8080",
+            "token",
+            None,
+            Default::default(),
+            None,
+            Default::default(),
+        )
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn matches_the_leaves() {
+        let todo = todo("Buy milk", "pending", 100);
+        assert!(TodoFilter::status(TodoStatus::Pending).matches(&todo));
+        assert!(!TodoFilter::status(TodoStatus::Completed).matches(&todo));
+        assert!(TodoFilter::status_in([TodoStatus::Completed, TodoStatus::Pending]).matches(&todo));
+        assert!(!TodoFilter::status_in([]).matches(&todo));
+        assert!(TodoFilter::title_contains("milk").matches(&todo));
+        assert!(!TodoFilter::title_contains("Milk").matches(&todo));
+        assert!(TodoFilter::created_between(at(100)..=at(100)).matches(&todo));
+        assert!(!TodoFilter::created_between(at(50)..at(100)).matches(&todo));
+        assert!(TodoFilter::created_between(..at(101)).matches(&todo));
+        assert!(TodoFilter::updated_between(at(110)..).matches(&todo));
+        assert!(!TodoFilter::updated_between(at(111)..).matches(&todo));
+    }
+
+    #[test]
+    fn unknown_status_matches_itself_only() {
+        let todo = todo("Old", "archived", 0);
+        assert!(TodoFilter::status(TodoStatus::Unknown("archived".to_owned())).matches(&todo));
+        assert!(!TodoFilter::status(TodoStatus::Pending).matches(&todo));
+    }
+
+    #[test]
+    fn combinators() {
+        let todo = todo("Buy milk", "pending", 100);
+        let pending = TodoFilter::status(TodoStatus::Pending);
+        let homework = TodoFilter::title_contains("Homework");
+        assert!(!(pending.clone() & homework.clone()).matches(&todo));
+        assert!((pending.clone() | homework.clone()).matches(&todo));
+        assert!((pending.clone() & !homework.clone()).matches(&todo));
+        assert!(TodoFilter::And(Vec::new()).matches(&todo));
+        assert!(!TodoFilter::Or(Vec::new()).matches(&todo));
+        // The nested combinators are flattened and a double negation is removed
+        assert!(matches!(
+            pending.clone() & homework.clone() & !!pending.clone(),
+            TodoFilter::And(filters) if filters.len() == 3
+        ));
+        assert!(matches!(!!homework, TodoFilter::TitleContains(_)));
+    }
+
+    #[test]
+    fn push_down_required_parts() {
+        let filter = TodoFilter::status(TodoStatus::Completed)
+            & TodoFilter::title_contains("milk")
+            & TodoFilter::created_between(at(0)..);
+        let todos = filter.push_down(new_todos());
+        assert!(todos.status == Some(TodoStatus::Completed));
+        assert_eq!(todos.title.as_deref(), Some("milk"));
+
+        let todos = TodoFilter::status_in([TodoStatus::Pending]).push_down(new_todos());
+        assert!(todos.status == Some(TodoStatus::Pending));
+    }
+
+    #[test]
+    fn push_down_skips_optional_parts() {
+        let filter = TodoFilter::status(TodoStatus::Completed) | TodoFilter::title_contains("milk");
+        let todos = filter.push_down(new_todos());
+        assert!(todos.status.is_none());
+        assert!(todos.title.is_none());
+
+        let filter = !TodoFilter::status(TodoStatus::Completed)
+            & TodoFilter::status_in([TodoStatus::Pending, TodoStatus::Progress]);
+        assert!(filter.push_down(new_todos()).status.is_none());
+    }
+
+    #[test]
+    fn push_down_keeps_the_explicit_filters() {
+        let filter = TodoFilter::status(TodoStatus::Completed) & TodoFilter::title_contains("milk");
+        let todos = filter.push_down(new_todos().status(TodoStatus::Pending).title("bread"));
+        assert!(todos.status == Some(TodoStatus::Pending));
+        assert_eq!(todos.title.as_deref(), Some("bread"));
+
+        let todos = TodoFilter::status(TodoStatus::Completed)
+            .push_down(new_todos().statuses(&[TodoStatus::Pending, TodoStatus::Progress]));
+        assert!(todos.status.is_none());
+        assert_eq!(todos.statuses.len(), 2);
+    }
+}
@@ -1,11 +1,27 @@
-use super::{Todo, TodoStatus};
-use crate::{api_helper::Endpoints, errors::Result as OxideResult};
+use super::{Todo, TodoFilter, TodoStatus, Workflow};
+use crate::{
+    api_helper::{Endpoints, Timeouts},
+    cache::Cache,
+    errors::{Result as OxideResult, SDKError},
+    validation::TitleRules,
+};
+use futures_util::future;
 use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    fmt,
     future::{Future, IntoFuture},
     pin::Pin,
+    str::FromStr,
+    time::SystemTime,
 };
 
+/// The page size used when the SDK pages through all the todos.
+pub(crate) const PAGE_SIZE: usize = 100;
+/// The default limit of the todos.
+pub(crate) const DEFAULT_LIMIT: usize = 10;
+
 /// The todo order, this is used to order the todos. (`newer`, `older`)
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -19,6 +35,9 @@ pub enum TodoOrder {
 }
 
 /// The todo order by, this is used to order the todos by. (`created_at`, `updated_at`)
+///
+/// It's written as `created_at` and `updated_at` by [`fmt::Display`], and as `createdat` and `updatedat` by serde
+/// (the server spelling, used by the JSON of [`TodosQuery`]). [`FromStr`] accepts both spellings.
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[serde(rename_all = "lowercase")]
@@ -40,6 +59,18 @@ pub struct Todos {
     pub(crate) base_url: String,
     /// The client token.
     pub(crate) token: String,
+    /// The client cache.
+    #[serde(skip)]
+    pub(crate) cache: Option<Cache>,
+    /// The client timeouts.
+    #[serde(skip)]
+    pub(crate) timeouts: Timeouts,
+    /// The client workflow, `None` if the status transitions are not validated.
+    #[serde(skip)]
+    pub(crate) workflow: Option<Workflow>,
+    /// The client title rules.
+    #[serde(skip)]
+    pub(crate) title_rules: TitleRules,
     /// The limit of the todos.
     /// This is the maximum amount of todos that can be in the list.
     pub(crate) limit: usize,
@@ -57,21 +88,84 @@ pub struct Todos {
     pub(crate) status: Option<TodoStatus>,
     /// Title filter of the todos.
     pub(crate) title: Option<String>,
+    /// The statuses of the todos, fetched concurrently and merged. Empty for one status or all the statuses.
+    #[serde(skip)]
+    pub(crate) statuses: Vec<TodoStatus>,
+    /// The local filter of the todos, evaluated on the fetched todos.
+    #[serde(skip)]
+    pub(crate) filter: Option<TodoFilter>,
+    /// `true` if the todos are always fetched from the server, without reading the cache.
+    #[serde(skip)]
+    pub(crate) fresh: bool,
+}
+
+/// The todos of each status of [`Todos::statuses`], merged with the order of the todos.
+///
+/// Each status keeps its own cursor, so paging through the merged todos fetches every todo once.
+struct MergedPages {
+    /// The order of the todos.
+    order: TodoOrder,
+    /// The order by of the todos.
+    order_by: TodoOrderBy,
+    /// The cursor of each status.
+    cursors: Vec<StatusCursor>,
+}
+
+/// The cursor of one status of the merged todos.
+struct StatusCursor {
+    /// The next page of the status.
+    page: Todos,
+    /// The fetched todos which are not merged yet.
+    buffer: VecDeque<Todo>,
+    /// `true` if all the todos of the status are fetched.
+    done: bool,
 }
 
 impl Todos {
     /// Create a new Todos type.
-    pub(crate) fn new(base_url: impl AsRef<str>, token: impl AsRef<str>) -> Self {
+    pub(crate) fn new(
+        base_url: impl AsRef<str>,
+        token: impl AsRef<str>,
+        cache: Option<Cache>,
+        timeouts: Timeouts,
+        workflow: Option<Workflow>,
+        title_rules: TitleRules,
+    ) -> Self {
         Self {
             base_url: base_url.as_ref().to_owned(),
             token: token.as_ref().to_owned(),
-            limit: 10,
+            cache,
+            timeouts,
+            workflow,
+            title_rules,
+            limit: DEFAULT_LIMIT,
             offset: 0,
             total: 0,
             order: TodoOrder::default(),
             order_by: TodoOrderBy::default(),
             status: None,
             title: None,
+            statuses: Vec::new(),
+            filter: None,
+            fresh: false,
+        }
+    }
+
+    /// Always fetch the todos from the server, without reading the cache. The fetched todos still invalidate
+    /// the cache when they are changed. Used by the polling and the conflict detection, which need the server version.
+    pub(crate) fn fresh(mut self) -> Self {
+        self.fresh = true;
+        self
+    }
+
+    /// Send the todos request, through the cache unless the todos are [`Todos::fresh`].
+    async fn send(&self) -> OxideResult<serde_json::Value> {
+        if self.fresh {
+            Endpoints::GetTodos(self).send(self.timeouts).await
+        } else {
+            Endpoints::GetTodos(self)
+                .send_cached(self.cache.as_ref(), self.timeouts)
+                .await
         }
     }
 
@@ -104,9 +198,50 @@ impl Todos {
     }
 
     /// Set the status of the todos.
-    /// This is the status of the todos.
+    /// This is the status of the todos. It replaces the statuses of [`Todos::statuses`].
     pub fn status(mut self, status: TodoStatus) -> Self {
         self.status = Some(status);
+        self.statuses.clear();
+        self
+    }
+
+    /// Set the statuses of the todos, the todos with any of the statuses. It replaces the status of [`Todos::status`].
+    ///
+    /// The server supports one status per request, so one request per status is sent concurrently, and the results
+    /// are merged with the order of the todos. The limit and the offset are of the merged todos.
+    /// When paging, each status keeps its own cursor, so every todo is fetched once.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::TodoStatus;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     // The open todos, newest first
+    ///     let todos = user
+    ///         .todos()
+    ///         .statuses(&[TodoStatus::Pending, TodoStatus::Progress])
+    ///         .limit(20)
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn statuses(mut self, statuses: &[TodoStatus]) -> Self {
+        self.statuses.clear();
+        for status in statuses {
+            if !self.statuses.contains(status) {
+                self.statuses.push(status.clone());
+            }
+        }
+        self.status = match self.statuses.as_slice() {
+            [status] => Some(status.clone()),
+            _ => None,
+        };
+        if self.statuses.len() < 2 {
+            self.statuses.clear();
+        }
         self
     }
 
@@ -116,22 +251,347 @@ impl Todos {
         self.title = Some(title.as_ref().to_owned());
         self
     }
+
+    /// Filter the todos with the filter expression, in addition to the other filters. See [`TodoFilter`].
+    ///
+    /// The server filters by what it supports, and the rest is evaluated locally while paging.
+    /// So the limit and the offset are of the filtered todos, and more than one request may be sent.
+    pub fn filter(mut self, filter: TodoFilter) -> Self {
+        self.filter = Some(match self.filter.take() {
+            Some(current) => current.and(filter),
+            None => filter,
+        });
+        self
+    }
+
+    /// Fetch all the todos with the filters, page by page starting from the offset. The limit is used as the page size.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::TodoStatus;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     // All the pending todos, 50 todos per request
+    ///     let todos = user.todos().status(TodoStatus::Pending).limit(50).all().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn all(self) -> OxideResult<Vec<Todo>> {
+        let mut todos = Vec::new();
+        self.for_each_page(|page| {
+            todos.extend(page);
+            Ok(())
+        })
+        .await?;
+        Ok(todos)
+    }
+
+    /// Fetch the todos with the filters page by page starting from the offset, and call `f` with each page.
+    /// Returns the amount of the fetched todos.
+    pub(crate) async fn for_each_page(
+        self,
+        mut f: impl FnMut(Vec<Todo>) -> OxideResult<()>,
+    ) -> OxideResult<usize> {
+        if let Some(filter) = self.filter.clone() {
+            return self.for_each_filtered_page(&filter, None, f).await;
+        }
+        if !self.statuses.is_empty() {
+            let mut merged = MergedPages::new(&self);
+            merged.skip(self.offset).await?;
+            let mut total = 0;
+            loop {
+                let todos = merged.next_page(self.limit).await?;
+                let len = todos.len();
+                total += len;
+                f(todos)?;
+                if self.limit == 0 || len < self.limit {
+                    return Ok(total);
+                }
+            }
+        }
+        let mut page = self;
+        let mut total = 0;
+        loop {
+            let todos = page.clone().await?;
+            let len = todos.len();
+            total += len;
+            f(todos)?;
+            if page.limit == 0 || len < page.limit {
+                break Ok(total);
+            }
+            page.offset += len;
+        }
+    }
+
+    /// Returns the amount of the todos with the filters, ignoring the limit and the offset.
+    ///
+    /// The total is read from the server with one request of one todo when possible, else the todos are counted
+    /// page by page. (When there is a [`Todos::filter`] or the server doesn't return the total)
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::types::TodoStatus;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let pending = user.todos().status(TodoStatus::Pending).count().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn count(self) -> OxideResult<usize> {
+        if let Some(total) = self.clone().server_total().await? {
+            return Ok(total);
+        }
+        self.offset(0)
+            .limit(PAGE_SIZE)
+            .for_each_page(|_| Ok(()))
+            .await
+    }
+
+    /// Returns the total of the todos returned by the server, `None` if the server doesn't return it
+    /// or if the todos can't be counted by the server. (With a local filter)
+    pub(crate) async fn server_total(self) -> OxideResult<Option<usize>> {
+        if self.filter.is_some() {
+            return Ok(None);
+        }
+        if !self.statuses.is_empty() {
+            let totals = self.statuses.iter().map(|status| {
+                Todos {
+                    status: Some(status.clone()),
+                    statuses: Vec::new(),
+                    ..self.clone()
+                }
+                .server_total()
+            });
+            return Ok(future::try_join_all(totals)
+                .await?
+                .into_iter()
+                .sum::<Option<usize>>());
+        }
+        let page = self.offset(0).limit(1);
+        Ok(page.send().await?["meta"]["total"]
+            .as_u64()
+            .map(|total| total as usize))
+    }
+
+    /// Fetch the todos of each status concurrently and merge them with the order of the todos.
+    async fn merge_statuses(self) -> OxideResult<Vec<Todo>> {
+        let mut merged = MergedPages::new(&self);
+        merged.skip(self.offset).await?;
+        merged.next_page(self.limit).await
+    }
+
+    /// Fetch the todos page by page from the server with the pushed down filters, and call `f` with the todos of each
+    /// page which match the filter, skipping the first `offset` matched todos and stopping after `limit` matched todos.
+    /// Returns the amount of the matched todos.
+    async fn for_each_filtered_page(
+        self,
+        filter: &TodoFilter,
+        limit: Option<usize>,
+        mut f: impl FnMut(Vec<Todo>) -> OxideResult<()>,
+    ) -> OxideResult<usize> {
+        let mut skip = self.offset;
+        let mut page = filter.push_down(Todos {
+            filter: None,
+            ..self.offset(0).limit(PAGE_SIZE)
+        });
+        // The statuses are merged with a cursor per status, instead of fetching the merged todos from the start
+        let mut merged = (!page.statuses.is_empty()).then(|| MergedPages::new(&page));
+        let mut total = 0;
+        while limit != Some(total) {
+            let todos = match &mut merged {
+                Some(merged) => merged.next_page(PAGE_SIZE).await?,
+                None => page.clone().await?,
+            };
+            let len = todos.len();
+            let mut matched: Vec<Todo> = todos
+                .into_iter()
+                .filter(|todo| filter.matches(todo))
+                .collect();
+            let skipped = skip.min(matched.len());
+            matched.drain(..skipped);
+            skip -= skipped;
+            if let Some(limit) = limit {
+                matched.truncate(limit - total);
+            }
+            total += matched.len();
+            if !matched.is_empty() {
+                f(matched)?;
+            }
+            if len < PAGE_SIZE {
+                break;
+            }
+            page.offset += len;
+        }
+        Ok(total)
+    }
+}
+
+impl MergedPages {
+    /// Create the cursors of the statuses of the todos, starting from the first todo.
+    fn new(todos: &Todos) -> Self {
+        Self {
+            order: todos.order.clone(),
+            order_by: todos.order_by.clone(),
+            cursors: todos
+                .statuses
+                .iter()
+                .map(|status| StatusCursor {
+                    page: Todos {
+                        status: Some(status.clone()),
+                        statuses: Vec::new(),
+                        filter: None,
+                        ..todos.clone().offset(0).limit(PAGE_SIZE)
+                    },
+                    buffer: VecDeque::new(),
+                    done: false,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the next merged todo, `None` if all the todos are merged.
+    /// The empty cursors are refilled concurrently first.
+    async fn next(&mut self) -> OxideResult<Option<Todo>> {
+        let refills = self
+            .cursors
+            .iter_mut()
+            .filter(|cursor| cursor.buffer.is_empty() && !cursor.done)
+            .map(StatusCursor::refill);
+        future::try_join_all(refills).await?;
+        let (order, order_by) = (&self.order, &self.order_by);
+        // The first cursor wins the ties, same as a stable sort of the statuses todos
+        let next = self
+            .cursors
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cursor)| cursor.buffer.front().map(|todo| (idx, todo)))
+            .min_by(|(_, a), (_, b)| order.compare(order_by, a, b))
+            .map(|(idx, _)| idx);
+        Ok(next.and_then(|idx| self.cursors[idx].buffer.pop_front()))
+    }
+
+    /// Returns the next `limit` merged todos, less if there are no more todos.
+    async fn next_page(&mut self, limit: usize) -> OxideResult<Vec<Todo>> {
+        let mut todos = Vec::with_capacity(limit.min(PAGE_SIZE));
+        while todos.len() < limit {
+            match self.next().await? {
+                Some(todo) => todos.push(todo),
+                None => break,
+            }
+        }
+        Ok(todos)
+    }
+
+    /// Skip the next `amount` merged todos.
+    async fn skip(&mut self, amount: usize) -> OxideResult<()> {
+        for _ in 0..amount {
+            if self.next().await?.is_none() {
+                break;
+            }
+        }
+        Ok(())
+    }
 }
 
-impl ToString for TodoOrder {
-    fn to_string(&self) -> String {
+impl StatusCursor {
+    /// Fetch the next page of the status into the buffer.
+    async fn refill(&mut self) -> OxideResult<()> {
+        let todos = self.page.clone().await?;
+        self.done = todos.len() < self.page.limit;
+        self.page.offset += todos.len();
+        self.buffer.extend(todos);
+        Ok(())
+    }
+}
+
+impl TodoOrder {
+    /// Compare two todos with this order, by the given field.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::types::{Todo, TodoOrder, TodoOrderBy};
+    ///
+    /// fn sort(todos: &mut [Todo]) {
+    ///     // Newest updated todos first
+    ///     todos.sort_by(|a, b| TodoOrder::Newer.compare(&TodoOrderBy::UpdatedAt, a, b));
+    /// }
+    /// ```
+    pub fn compare(&self, order_by: &TodoOrderBy, a: &Todo, b: &Todo) -> Ordering {
+        let ordering = order_by.time_of(a).cmp(&order_by.time_of(b));
         match self {
-            Self::Newer => "newer".to_owned(),
-            Self::Older => "older".to_owned(),
+            Self::Newer => ordering.reverse(),
+            Self::Older => ordering,
         }
     }
+
+    /// Sort the todos locally with this order, by the given field. Same as the server ordering.
+    pub fn sort(&self, order_by: &TodoOrderBy, todos: &mut [Todo]) {
+        todos.sort_by(|a, b| self.compare(order_by, a, b))
+    }
 }
 
-impl ToString for TodoOrderBy {
-    fn to_string(&self) -> String {
+impl TodoOrderBy {
+    /// Returns the time of the todo which this order by refers to.
+    pub fn time_of(&self, todo: &Todo) -> SystemTime {
         match self {
-            Self::CreatedAt => "created_at".to_owned(),
-            Self::UpdatedAt => "updated_at".to_owned(),
+            Self::CreatedAt => todo.created_at_system(),
+            Self::UpdatedAt => todo.updated_at_system(),
+        }
+    }
+}
+
+impl fmt::Display for TodoOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Newer => "newer",
+            Self::Older => "older",
+        })
+    }
+}
+
+impl fmt::Display for TodoOrderBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+        })
+    }
+}
+
+impl FromStr for TodoOrder {
+    type Err = SDKError;
+
+    /// Parse the order, `newer` or `older`. Returns [`SDKError::Validation`] if the order is unknown.
+    fn from_str(order: &str) -> Result<Self, Self::Err> {
+        match order {
+            "newer" => Ok(Self::Newer),
+            "older" => Ok(Self::Older),
+            _ => Err(SDKError::validation(
+                "order",
+                format!("expected `newer` or `older`, found `{order}`"),
+            )),
+        }
+    }
+}
+
+impl FromStr for TodoOrderBy {
+    type Err = SDKError;
+
+    /// Parse the order by, `created_at` or `updated_at`, the serde spelling (`createdat` or `updatedat`)
+    /// is accepted too. Returns [`SDKError::Validation`] if the order by is unknown.
+    fn from_str(order_by: &str) -> Result<Self, Self::Err> {
+        match order_by {
+            "created_at" | "createdat" => Ok(Self::CreatedAt),
+            "updated_at" | "updatedat" => Ok(Self::UpdatedAt),
+            _ => Err(SDKError::validation(
+                "order_by",
+                format!("expected `created_at` or `updated_at`, found `{order_by}`"),
+            )),
         }
     }
 }
@@ -142,9 +602,35 @@ impl IntoFuture for Todos {
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
-            Endpoints::GetTodos(&self)
-                .await
-                .map(|d| serde_json::from_value(d["data"].clone()).unwrap())
+            if let Some(filter) = self.filter.clone() {
+                let mut todos = Vec::new();
+                let limit = self.limit;
+                self.for_each_filtered_page(&filter, Some(limit), |page| {
+                    todos.extend(page);
+                    Ok(())
+                })
+                .await?;
+                return Ok(todos);
+            }
+            if !self.statuses.is_empty() {
+                return self.merge_statuses().await;
+            }
+            self.send().await.and_then(|d| {
+                serde_json::from_value::<Vec<serde_json::Value>>(d["data"].clone())?
+                    .into_iter()
+                    .map(|v| {
+                        Todo::from_value(
+                            &self.base_url,
+                            &self.token,
+                            self.cache.clone(),
+                            self.timeouts,
+                            self.workflow.clone(),
+                            self.title_rules,
+                            v,
+                        )
+                    })
+                    .collect()
+            })
         })
     }
 }
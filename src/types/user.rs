@@ -1,11 +1,21 @@
-use super::{Todo, Todos};
-use crate::{api_helper::Endpoints, errors::Result as OxideResult};
+use super::{
+    ChangeSet, NoStatus, Todo, TodoBuilder, TodoOrder, TodoOrderBy, TodoPatch, Todos, Workflow,
+    PAGE_SIZE,
+};
+use crate::{
+    api_helper::{Endpoints, Timeouts},
+    cache::Cache,
+    errors::Result as OxideResult,
+    query::QueryStore,
+    validation::TitleRules,
+};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 /// A oxide todo user. This is the user which is registered and logged in to the server.
 ///
 /// You can create a new user by using [`Client::register`], [`Client::login`] or [`Client::login_by_token`], and you can revoke the token by using [`User::revoke_token`].
-/// You can create a new todo by using [`User::create_todo`] and you can get a todo by using [`User::todo_by_uuid`].
+/// You can create a new todo by using [`User::create_todo`], get a todo by using [`User::todo_by_uuid`] and update a todo by using [`User::update_todo`].
 ///
 /// [`Client::register`]: crate::Client::register
 /// [`Client::login`]: crate::Client::login
@@ -23,6 +33,21 @@ pub struct User {
     pub(crate) name: Option<String>,
     /// The user token, which is used to authenticate the user.
     pub(crate) token: String,
+    /// The client cache.
+    #[serde(skip)]
+    pub(crate) cache: Option<Cache>,
+    /// The client timeouts.
+    #[serde(skip)]
+    pub(crate) timeouts: Timeouts,
+    /// The client workflow, `None` if the status transitions are not validated.
+    #[serde(skip)]
+    pub(crate) workflow: Option<Workflow>,
+    /// The client title rules.
+    #[serde(skip)]
+    pub(crate) title_rules: TitleRules,
+    /// The client saved queries, `None` if there is no query store.
+    #[serde(skip)]
+    pub(crate) queries: Option<QueryStore>,
 }
 
 impl User {
@@ -35,9 +60,10 @@ impl User {
     pub fn token(&self) -> &str {
         &self.token
     }
-    /// Create new todo.
+    /// Create new todo with the given title.
     /// ### Note
-    /// You cannot create a todo without a status. So you need to set the status of the todo after this.
+    /// You cannot create a todo without a status. So you need to set the status of the todo after this,
+    /// otherwise the todo builder will not be awaitable. See [`TodoBuilder`].
     /// ### Example
     /// ```rust |no_run
     /// use oxide_todo_sdk::Client;
@@ -48,19 +74,75 @@ impl User {
     /// async fn main() -> OxideResult<()> {
     ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
     ///     let todo = user.create_todo("My new todo")
-    ///         .status(TodoStatus::Completed) // Need to set the status of the todo before sending the request
+    ///         .set_status(TodoStatus::Completed) // Need to set the status of the todo before sending the request
     ///         .await?;
     ///     Ok(())
     /// }
-    pub fn create_todo(&self, title: impl Into<String>) -> Todo {
-        Todo {
+    /// ```
+    pub fn create_todo(&self, title: impl Into<String>) -> TodoBuilder<NoStatus> {
+        TodoBuilder {
             base_url: self.base_url.clone(),
             token: self.token.clone(),
-            title: Some(title.into()),
-            ..Default::default()
+            cache: self.cache.clone(),
+            timeouts: self.timeouts,
+            workflow: self.workflow.clone(),
+            title_rules: self.title_rules,
+            title: title.into(),
+            status: NoStatus,
         }
     }
-    /// Returns a todo by uuid. await the future after this to get the todo. Or await it after you set the status or title to update the todo on the server.
+
+    /// Fetch a todo by uuid from the server.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    /// use uuid::Uuid;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let todo = user.todo_by_uuid(Uuid::new_v4()).await?;
+    ///     println!("{}", todo.title());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn todo_by_uuid(&self, uuid: Uuid) -> OxideResult<Todo> {
+        self.get_todo(uuid, true).await
+    }
+
+    /// Fetch a todo by uuid from the server, without reading the client cache.
+    #[cfg(feature = "sync")]
+    pub(crate) async fn fresh_todo_by_uuid(&self, uuid: Uuid) -> OxideResult<Todo> {
+        self.get_todo(uuid, false).await
+    }
+
+    /// Fetch a todo by uuid, through the client cache if `cached` is `true`.
+    async fn get_todo(&self, uuid: Uuid, cached: bool) -> OxideResult<Todo> {
+        let endpoint = Endpoints::GetTodo {
+            base_url: &self.base_url,
+            token: &self.token,
+            uuid: &uuid,
+        };
+        let value = if cached {
+            endpoint
+                .send_cached(self.cache.as_ref(), self.timeouts)
+                .await?
+        } else {
+            endpoint.send(self.timeouts).await?
+        };
+        Todo::from_value(
+            &self.base_url,
+            &self.token,
+            self.cache.clone(),
+            self.timeouts,
+            self.workflow.clone(),
+            self.title_rules,
+            value,
+        )
+    }
+
+    /// Returns an empty patch of the todo with the given uuid, set the fields you want to update then await it to send it to the server.
     /// ### Example
     /// ```rust |no_run
     /// use oxide_todo_sdk::Client;
@@ -71,18 +153,25 @@ impl User {
     /// #[tokio::main]
     /// async fn main() -> OxideResult<()> {
     ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
-    ///     let todo = user.todo_by_uuid(Uuid::new_v4()) // Get a todo by uuid
-    ///         .status(TodoStatus::Completed); // Update the status of the todo
-    ///         .await?; // Send the update request to the server
+    ///     let todo = user.update_todo(Uuid::new_v4()) // The todo uuid
+    ///         .set_status(TodoStatus::Completed) // Update the status of the todo
+    ///         .update() // Send the update request to the server
+    ///         .await?;
     ///     Ok(())
     /// }
     /// ```
-    pub fn todo_by_uuid(&self, uuid: Uuid) -> Todo {
-        Todo {
+    pub fn update_todo(&self, uuid: Uuid) -> TodoPatch {
+        TodoPatch {
             base_url: self.base_url.clone(),
             token: self.token.clone(),
-            uuid: Some(uuid),
-            ..Default::default()
+            cache: self.cache.clone(),
+            timeouts: self.timeouts,
+            workflow: self.workflow.clone(),
+            title_rules: self.title_rules,
+            uuid,
+            title: None,
+            status: None,
+            current_status: None,
         }
     }
 
@@ -107,10 +196,15 @@ impl User {
             base_url: &self.base_url,
             token: &self.token,
         }
+        .send(self.timeouts)
         .await?;
         Ok(Self {
             base_url: self.base_url,
-            ..serde_json::from_value(user).unwrap()
+            cache: self.cache,
+            timeouts: self.timeouts,
+            workflow: self.workflow,
+            title_rules: self.title_rules,
+            ..serde_json::from_value(user)?
         })
     }
 
@@ -129,7 +223,112 @@ impl User {
     /// }
     /// ```
     pub fn todos(&self) -> Todos {
-        Todos::new(&self.base_url, &self.token)
+        Todos::new(
+            &self.base_url,
+            &self.token,
+            self.cache.clone(),
+            self.timeouts,
+            self.workflow.clone(),
+            self.title_rules,
+        )
+    }
+
+    /// Returns the todos created or updated since the checkpoint (inclusive), and the new checkpoint.
+    ///
+    /// This pages through the todos ordered by [`TodoOrderBy::UpdatedAt`] with [`TodoOrder::Newer`] and stops at the
+    /// first todo older than the checkpoint, so it's cheap when there are few changes. The todos updated at the
+    /// checkpoint second are returned again, so apply the changes idempotently. Deleted todos are not returned,
+    /// use [`User::deleted_todos`] periodically to detect them.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let changes = user.changes_since(0).await?; // All the todos
+    ///     // Later
+    ///     let changes = user.changes_since(changes.checkpoint()).await?;
+    ///     for todo in changes.updated() {
+    ///         // Upsert the todo in your replica
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn changes_since(&self, checkpoint: u64) -> OxideResult<ChangeSet> {
+        let mut page = self
+            .todos()
+            .fresh()
+            .order(TodoOrder::Newer)
+            .order_by(TodoOrderBy::UpdatedAt)
+            .limit(PAGE_SIZE);
+        let mut updated = Vec::new();
+        loop {
+            let todos = page.clone().await?;
+            let len = todos.len();
+            let before = updated.len();
+            updated.extend(todos.into_iter().filter(|t| t.updated_at >= checkpoint));
+            // The page is not full or it contains older todos, no more changes
+            if len < PAGE_SIZE || updated.len() - before < len {
+                break;
+            }
+            page.offset += len;
+        }
+        Ok(ChangeSet {
+            checkpoint: updated
+                .iter()
+                .map(Todo::updated_at)
+                .max()
+                .unwrap_or(checkpoint)
+                .max(checkpoint),
+            updated,
+        })
+    }
+
+    /// Returns the uuids from `known` which are no longer on the server. This fetches all the todos uuids, so
+    /// call it periodically to reconcile the deleted todos, and use [`User::changes_since`] for the changes.
+    pub async fn deleted_todos(
+        &self,
+        known: impl IntoIterator<Item = Uuid>,
+    ) -> OxideResult<Vec<Uuid>> {
+        let server: HashSet<Uuid> = self
+            .todos()
+            .fresh()
+            .limit(PAGE_SIZE)
+            .all()
+            .await?
+            .iter()
+            .map(Todo::uuid)
+            .collect();
+        Ok(known
+            .into_iter()
+            .filter(|uuid| !server.contains(uuid))
+            .collect())
+    }
+
+    /// Deletes a todo by uuid, without fetching it first.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    /// use uuid::Uuid;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     user.delete_todo(Uuid::new_v4()).await // The todo uuid
+    /// }
+    /// ```
+    pub async fn delete_todo(&self, uuid: Uuid) -> OxideResult<()> {
+        Endpoints::DeleteTodo {
+            base_url: &self.base_url,
+            token: &self.token,
+            uuid: &uuid,
+        }
+        .send_cached(self.cache.as_ref(), self.timeouts)
+        .await
+        .map(|_| ())
     }
 
     /// Deletes all the todos of the user.
@@ -150,6 +349,7 @@ impl User {
             base_url: &self.base_url,
             token: &self.token,
         }
+        .send_cached(self.cache.as_ref(), self.timeouts)
         .await?;
         Ok(())
     }
@@ -0,0 +1,157 @@
+use super::{Todo, User, PAGE_SIZE};
+use crate::errors::Result as OxideResult;
+use futures_util::{stream, Stream};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// The maximum delay between two polls, as a multiple of the watch interval.
+const MAX_BACKOFF: u32 = 8;
+
+/// A change of a todo, detected by [`User::watch`].
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum TodoEvent {
+    /// A new todo has been created.
+    Created(Todo),
+    /// A todo has been updated, with the old and the new version.
+    Updated {
+        /// The old version of the todo.
+        old: Todo,
+        /// The new version of the todo.
+        new: Todo,
+    },
+    /// A todo has been deleted, with its last known version.
+    Deleted(Todo),
+}
+
+/// The state of the watch stream.
+struct Watcher {
+    /// The watched user.
+    user: User,
+    /// The todos of the last poll, by uuid. `None` before the first poll.
+    snapshot: Option<HashMap<Uuid, Todo>>,
+    /// The detected events which are not yielded yet.
+    events: VecDeque<TodoEvent>,
+    /// The watch interval.
+    interval: Duration,
+    /// The delay before the next poll.
+    delay: Duration,
+    /// `false` before the first poll, the first poll is not delayed.
+    polled: bool,
+}
+
+impl TodoEvent {
+    /// Returns the uuid of the changed todo.
+    pub fn uuid(&self) -> Uuid {
+        match self {
+            Self::Created(todo) | Self::Updated { new: todo, .. } | Self::Deleted(todo) => {
+                todo.uuid
+            }
+        }
+    }
+}
+
+impl Watcher {
+    /// Fetch the todos and queue the events of the changed todos since the last poll.
+    async fn poll(&mut self) -> OxideResult<()> {
+        let todos: HashMap<Uuid, Todo> = self
+            .user
+            .todos()
+            .fresh()
+            .limit(PAGE_SIZE)
+            .all()
+            .await?
+            .into_iter()
+            .map(|todo| (todo.uuid, todo))
+            .collect();
+        if let Some(mut snapshot) = self.snapshot.take() {
+            for todo in todos.values() {
+                match snapshot.remove(&todo.uuid) {
+                    None => self.events.push_back(TodoEvent::Created(todo.clone())),
+                    Some(old) if old.updated_at != todo.updated_at => {
+                        self.events.push_back(TodoEvent::Updated {
+                            old,
+                            new: todo.clone(),
+                        })
+                    }
+                    Some(_) => (),
+                }
+            }
+            // The remaining todos are deleted
+            self.events
+                .extend(snapshot.into_values().map(TodoEvent::Deleted));
+        }
+        self.snapshot = Some(todos);
+        Ok(())
+    }
+
+    /// Wait the delay then poll, and update the delay. It's doubled (up to a limit) when nothing is changed.
+    async fn next(&mut self) -> OxideResult<()> {
+        if self.polled {
+            tokio::time::sleep(self.delay).await;
+        }
+        self.polled = true;
+        let result = self.poll().await;
+        self.delay = if result.is_ok() && !self.events.is_empty() {
+            self.interval
+        } else {
+            (self.delay * 2).min(self.interval * MAX_BACKOFF)
+        };
+        result
+    }
+}
+
+impl User {
+    /// Watch the todos of the user, by polling the server. (Requires the `watch` feature)
+    ///
+    /// The first poll is the initial snapshot and yields no events, after it the todos are polled every `interval`
+    /// and compared with the previous poll by uuid and `updated_at`. When nothing changes the delay is doubled,
+    /// up to 8 times the interval, and it's back to the interval after the first change.
+    /// The poll errors are yielded, and the watch keeps polling after them.
+    /// ### Example
+    /// ```rust |no_run
+    /// use oxide_todo_sdk::Client;
+    /// use oxide_todo_sdk::errors::Result as OxideResult;
+    /// use oxide_todo_sdk::types::TodoEvent;
+    /// use futures_util::{pin_mut, StreamExt};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> OxideResult<()> {
+    ///     let user = Client::new("http://localhost:8080").login_by_token("YOUR_TOKEN");
+    ///     let events = user.watch(Duration::from_secs(5));
+    ///     pin_mut!(events);
+    ///     while let Some(event) = events.next().await {
+    ///         match event? {
+    ///             TodoEvent::Created(todo) => println!("Created: {}", todo.title()),
+    ///             TodoEvent::Updated { new, .. } => println!("Updated: {}", new.title()),
+    ///             TodoEvent::Deleted(todo) => println!("Deleted: {}", todo.title()),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn watch(&self, interval: Duration) -> impl Stream<Item = OxideResult<TodoEvent>> {
+        let watcher = Watcher {
+            user: self.clone(),
+            snapshot: None,
+            events: VecDeque::new(),
+            interval,
+            delay: interval,
+            polled: false,
+        };
+        stream::unfold(watcher, |mut watcher| async move {
+            loop {
+                if let Some(event) = watcher.events.pop_front() {
+                    return Some((Ok(event), watcher));
+                }
+                if let Err(err) = watcher.next().await {
+                    return Some((Err(err), watcher));
+                }
+            }
+        })
+    }
+}
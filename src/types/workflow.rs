@@ -0,0 +1,240 @@
+use super::TodoStatus;
+use crate::errors::InvalidTransition;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// The allowed transitions between the todo statuses, validated on the client before updating the status.
+///
+/// Set it with [`Client::with_workflow`], then the status updates of the client users are validated
+/// and a forbidden transition returns [`SDKError::InvalidTransition`]. Keeping the same status is always allowed.
+///
+/// The default workflow allows all the transitions, except from a cancelled todo which can only be reopened,
+/// and a completed todo which can only be reopened or started again.
+///
+/// A status which is not known by the SDK ([`TodoStatus::Unknown`]) follows its own transitions if there are any,
+/// else any transition from it is allowed (see [`Workflow::allow_from_unknown`]), since the SDK doesn't know its rules.
+/// The transitions to an unknown status are only allowed when they are allowed explicitly.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::types::{TodoStatus, Workflow};
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let workflow = Workflow::new()
+///         .allow(TodoStatus::Pending, [TodoStatus::Progress, TodoStatus::Cancelled])
+///         .allow(TodoStatus::Progress, [TodoStatus::Completed, TodoStatus::Cancelled])
+///         .allow(TodoStatus::Cancelled, [TodoStatus::Pending]);
+///     let user = Client::new("http://localhost:8080")
+///         .with_workflow(workflow)
+///         .login_by_token("YOUR_TOKEN");
+///     let todo = user.create_todo("My new todo").set_status(TodoStatus::Pending).await?;
+///     // Error: the todo must be in progress before it's completed
+///     assert!(todo.complete().await.is_err());
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Client::with_workflow`]: crate::Client::with_workflow
+/// [`SDKError::InvalidTransition`]: crate::errors::SDKError::InvalidTransition
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct Workflow {
+    /// The transitions, shared by the clones of the workflow.
+    rules: Arc<Rules>,
+}
+
+/// The transitions of a workflow.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct Rules {
+    /// The allowed statuses from each status.
+    transitions: HashMap<TodoStatus, HashSet<TodoStatus>>,
+    /// `true` if any transition is allowed from an unknown status without its own transitions.
+    from_unknown: bool,
+}
+
+impl Workflow {
+    /// Create a workflow without transitions, allow them with [`Workflow::allow`].
+    pub fn new() -> Self {
+        Self {
+            rules: Arc::new(Rules {
+                transitions: HashMap::new(),
+                from_unknown: true,
+            }),
+        }
+    }
+
+    /// Allow any transition from an unknown status which has no transitions of its own. (default: `true`)
+    /// When it's `false`, a todo with an unknown status can only change to the statuses allowed from it explicitly.
+    pub fn allow_from_unknown(mut self, allow: bool) -> Self {
+        Arc::make_mut(&mut self.rules).from_unknown = allow;
+        self
+    }
+
+    /// Allow the transitions from the status to each of the given statuses.
+    pub fn allow(mut self, from: TodoStatus, to: impl IntoIterator<Item = TodoStatus>) -> Self {
+        Arc::make_mut(&mut self.rules)
+            .transitions
+            .entry(from)
+            .or_default()
+            .extend(to);
+        self
+    }
+
+    /// Returns `true` if the transition is allowed. Keeping the same status is always allowed.
+    pub fn is_allowed(&self, from: &TodoStatus, to: &TodoStatus) -> bool {
+        if from == to {
+            return true;
+        }
+        match self.rules.transitions.get(from) {
+            Some(allowed) => allowed.contains(to),
+            None => self.rules.from_unknown && !from.is_known() && to.is_known(),
+        }
+    }
+
+    /// Returns the allowed statuses from the status, in the order of [`TodoStatus`] declaration,
+    /// then the explicitly allowed unknown statuses sorted by name.
+    pub fn allowed_from(&self, from: &TodoStatus) -> Vec<TodoStatus> {
+        let mut unknown: Vec<TodoStatus> = self
+            .rules
+            .transitions
+            .get(from)
+            .into_iter()
+            .flatten()
+            .filter(|to| !to.is_known() && *to != from)
+            .cloned()
+            .collect();
+        unknown.sort_by_key(ToString::to_string);
+        [
+            TodoStatus::Completed,
+            TodoStatus::Progress,
+            TodoStatus::Pending,
+            TodoStatus::Cancelled,
+        ]
+        .into_iter()
+        .filter(|to| to != from && self.is_allowed(from, to))
+        .chain(unknown)
+        .collect()
+    }
+
+    /// Validate the transition, returns an [`InvalidTransition`] error with the allowed statuses if it's forbidden.
+    pub fn validate(&self, from: &TodoStatus, to: &TodoStatus) -> Result<(), InvalidTransition> {
+        if self.is_allowed(from, to) {
+            return Ok(());
+        }
+        Err(InvalidTransition {
+            from: from.clone(),
+            to: to.clone(),
+            allowed: self.allowed_from(from),
+        })
+    }
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        Self::new()
+            .allow(
+                TodoStatus::Pending,
+                [
+                    TodoStatus::Progress,
+                    TodoStatus::Completed,
+                    TodoStatus::Cancelled,
+                ],
+            )
+            .allow(
+                TodoStatus::Progress,
+                [
+                    TodoStatus::Pending,
+                    TodoStatus::Completed,
+                    TodoStatus::Cancelled,
+                ],
+            )
+            .allow(
+                TodoStatus::Completed,
+                [TodoStatus::Pending, TodoStatus::Progress],
+            )
+            .allow(TodoStatus::Cancelled, [TodoStatus::Pending])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unknown(status: &str) -> TodoStatus {
+        TodoStatus::Unknown(status.to_owned())
+    }
+
+    #[test]
+    fn default_allowed_from() {
+        let workflow = Workflow::default();
+        assert!(
+            workflow.allowed_from(&TodoStatus::Pending)
+                == [
+                    TodoStatus::Completed,
+                    TodoStatus::Progress,
+                    TodoStatus::Cancelled
+                ]
+        );
+        assert!(workflow.allowed_from(&TodoStatus::Cancelled) == [TodoStatus::Pending]);
+    }
+
+    #[test]
+    fn keeping_the_status_is_always_allowed() {
+        let workflow = Workflow::new();
+        assert!(workflow.is_allowed(&TodoStatus::Completed, &TodoStatus::Completed));
+        assert!(workflow.is_allowed(&unknown("archived"), &unknown("archived")));
+        assert!(workflow.allowed_from(&TodoStatus::Completed).is_empty());
+    }
+
+    #[test]
+    fn validate_returns_the_allowed_statuses() {
+        let err = Workflow::default()
+            .validate(&TodoStatus::Cancelled, &TodoStatus::Completed)
+            .unwrap_err();
+        assert!(err.from() == &TodoStatus::Cancelled);
+        assert!(err.to() == &TodoStatus::Completed);
+        assert!(err.allowed() == [TodoStatus::Pending]);
+    }
+
+    #[test]
+    fn any_known_transition_from_an_unknown_status() {
+        let workflow = Workflow::default();
+        assert!(workflow.is_allowed(&unknown("archived"), &TodoStatus::Pending));
+        assert!(!workflow.is_allowed(&unknown("archived"), &unknown("blocked")));
+        assert!(
+            workflow.allowed_from(&unknown("archived"))
+                == [
+                    TodoStatus::Completed,
+                    TodoStatus::Progress,
+                    TodoStatus::Pending,
+                    TodoStatus::Cancelled
+                ]
+        );
+    }
+
+    #[test]
+    fn unknown_status_transitions_can_be_configured() {
+        let workflow = Workflow::default()
+            .allow_from_unknown(false)
+            .allow(unknown("archived"), [TodoStatus::Pending])
+            .allow(TodoStatus::Completed, [unknown("archived")]);
+        assert!(workflow.is_allowed(&unknown("archived"), &TodoStatus::Pending));
+        assert!(!workflow.is_allowed(&unknown("archived"), &TodoStatus::Completed));
+        assert!(!workflow.is_allowed(&unknown("blocked"), &TodoStatus::Pending));
+        assert!(workflow.is_allowed(&TodoStatus::Completed, &unknown("archived")));
+        assert!(!workflow.is_allowed(&TodoStatus::Pending, &unknown("archived")));
+        assert!(
+            workflow.allowed_from(&TodoStatus::Completed)
+                == [
+                    TodoStatus::Progress,
+                    TodoStatus::Pending,
+                    unknown("archived")
+                ]
+        );
+    }
+}
@@ -0,0 +1,549 @@
+//! The validation module. This module contains the client-side validation of the inputs before they are sent to
+//! the server, so the invalid inputs are rejected without a round trip.
+//!
+//! The rules are set on the [`Client`] and shared with its users, an invalid input returns [`SDKError::Validation`]
+//! with the invalid field and the reason.
+//!
+//! [`Client`]: crate::Client
+//! [`SDKError::Validation`]: crate::errors::SDKError::Validation
+
+use crate::errors::SDKError;
+
+/// The rules of the todo titles, validated before creating a todo or updating its title.
+///
+/// The title is trimmed first, then it must not be empty, must not be longer than the maximum length
+/// and must not contain control characters (e.g. a new line or a tab).
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::validation::TitleRules;
+/// use oxide_todo_sdk::types::TodoStatus;
+/// use oxide_todo_sdk::errors::{Error, Result as OxideResult, SDKError};
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let user = Client::new("http://localhost:8080")
+///         .with_title_rules(TitleRules::new().max_len(100))
+///         .login_by_token("YOUR_TOKEN");
+///     match user.create_todo("   ").set_status(TodoStatus::Pending).await {
+///         Err(Error::SDKError(SDKError::Validation { field, reason })) => println!("Invalid {field}: {reason}"),
+///         _ => unreachable!("The title is empty"),
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct TitleRules {
+    /// The maximum length of the title in characters, `None` for no maximum.
+    max_len: Option<usize>,
+    /// `true` to normalize the title to the Unicode NFC form.
+    #[cfg(feature = "unicode-normalization")]
+    normalize: bool,
+}
+
+impl TitleRules {
+    /// Create the default rules, without a maximum length.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum length of the title, in characters. (no maximum by default)
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Normalize the title to the Unicode NFC form before validating it, so the same title is always sent
+    /// with the same characters. (disabled by default, requires the `unicode-normalization` feature)
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Validate the title, returns the trimmed (and normalized) title to send to the server,
+    /// or [`SDKError::Validation`] if the title is invalid.
+    pub fn validate(&self, title: &str) -> Result<String, SDKError> {
+        let title = title.trim();
+        #[cfg(feature = "unicode-normalization")]
+        let title: String = if self.normalize {
+            unicode_normalization::UnicodeNormalization::nfc(title).collect()
+        } else {
+            title.to_owned()
+        };
+        #[cfg(not(feature = "unicode-normalization"))]
+        let title = title.to_owned();
+
+        if title.is_empty() {
+            return Err(SDKError::validation("title", "must not be empty"));
+        }
+        if let Some(max_len) = self.max_len {
+            let len = title.chars().count();
+            if len > max_len {
+                return Err(SDKError::validation(
+                    "title",
+                    format!("must be at most {max_len} characters, found {len}"),
+                ));
+            }
+        }
+        if let Some((idx, ch)) = title.chars().enumerate().find(|(_, ch)| ch.is_control()) {
+            return Err(SDKError::validation(
+                "title",
+                format!(
+                    "must not contain control characters, found U+{:04X} at character {}",
+                    ch as u32,
+                    idx + 1
+                ),
+            ));
+        }
+        Ok(title)
+    }
+}
+
+/// The rules of the usernames, validated before registering a user.
+///
+/// The login usernames are only checked to be not empty, so the users registered before the rules can still log in.
+///
+/// The username must not be shorter than the minimum length, must not be longer than the maximum length
+/// and all its characters must be in the charset. By default the username must not be empty and must not contain
+/// whitespace or control characters.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::validation::UsernameRules;
+///
+/// let rules = UsernameRules::new()
+///     .min_len(3)
+///     .max_len(32)
+///     .charset(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+/// // Give instant feedback in a signup form with the same rules
+/// if let Err(err) = rules.validate("a b") {
+///     println!("{err}");
+/// }
+/// ```
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct UsernameRules {
+    /// The minimum length of the username, in characters.
+    min_len: usize,
+    /// The maximum length of the username in characters, `None` for no maximum.
+    max_len: Option<usize>,
+    /// Returns `true` if the character is allowed in the username.
+    charset: fn(char) -> bool,
+}
+
+/// The password policy, validated before registering a user.
+///
+/// By default the password must not be empty, use [`PasswordPolicy::recommended`] for a stricter policy.
+/// The login passwords are not validated by the policy, so the users registered before the policy can still log in.
+/// ### Example
+/// ```rust |no_run
+/// use oxide_todo_sdk::Client;
+/// use oxide_todo_sdk::validation::PasswordPolicy;
+/// use oxide_todo_sdk::errors::Result as OxideResult;
+///
+/// #[tokio::main]
+/// async fn main() -> OxideResult<()> {
+///     let policy = PasswordPolicy::new()
+///         .min_len(10)
+///         .require_digit(true)
+///         .ban(["password123", "qwertyuiop"]);
+///     // Give instant feedback in a signup form with the same policy
+///     for reason in policy.violations("password") {
+///         println!("The password {reason}");
+///     }
+///     let client = Client::new("http://localhost:8080").with_password_policy(policy);
+///     // Error: the password is too short and has no digit
+///     assert!(client.register("username", "password").await.is_err());
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PasswordPolicy {
+    /// The minimum length of the password, in characters.
+    min_len: usize,
+    /// The maximum length of the password in characters, `None` for no maximum.
+    max_len: Option<usize>,
+    /// `true` if the password must contain a lowercase letter.
+    require_lowercase: bool,
+    /// `true` if the password must contain an uppercase letter.
+    require_uppercase: bool,
+    /// `true` if the password must contain a digit.
+    require_digit: bool,
+    /// `true` if the password must contain a symbol, a character which is not alphanumeric or whitespace.
+    require_symbol: bool,
+    /// The banned passwords, lowercased.
+    banned: Vec<String>,
+}
+
+impl UsernameRules {
+    /// Create the default rules, a username which is not empty and has no whitespace or control characters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum length of the username, in characters. (default: `1`)
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Set the maximum length of the username, in characters. (no maximum by default)
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Set the allowed characters of the username. (default: no whitespace or control characters)
+    pub fn charset(mut self, charset: fn(char) -> bool) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Validate the username, returns [`SDKError::Validation`] if the username is invalid.
+    pub fn validate(&self, username: &str) -> Result<(), SDKError> {
+        let len = username.chars().count();
+        if len == 0 {
+            return Err(SDKError::validation("username", "must not be empty"));
+        }
+        if len < self.min_len {
+            return Err(SDKError::validation(
+                "username",
+                format!("must be at least {} characters, found {len}", self.min_len),
+            ));
+        }
+        if let Some(max_len) = self.max_len {
+            if len > max_len {
+                return Err(SDKError::validation(
+                    "username",
+                    format!("must be at most {max_len} characters, found {len}"),
+                ));
+            }
+        }
+        if let Some((idx, ch)) = username
+            .chars()
+            .enumerate()
+            .find(|(_, ch)| !(self.charset)(*ch))
+        {
+            return Err(SDKError::validation(
+                "username",
+                format!(
+                    "must not contain the character U+{:04X} at character {}",
+                    ch as u32,
+                    idx + 1
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for UsernameRules {
+    fn default() -> Self {
+        Self {
+            min_len: 1,
+            max_len: None,
+            charset: |ch| !ch.is_whitespace() && !ch.is_control(),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Create the default policy, a password which is not empty.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recommended policy, at least 8 characters with a lowercase letter, an uppercase letter
+    /// and a digit, and not one of the most common passwords.
+    pub fn recommended() -> Self {
+        Self::new()
+            .min_len(8)
+            .require_lowercase(true)
+            .require_uppercase(true)
+            .require_digit(true)
+            .ban([
+                "password",
+                "password1",
+                "password123",
+                "12345678",
+                "123456789",
+                "1234567890",
+                "qwerty123",
+                "qwertyuiop",
+                "iloveyou",
+                "letmein1",
+                "welcome1",
+                "admin123",
+            ])
+    }
+
+    /// Set the minimum length of the password, in characters. (default: `1`)
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Set the maximum length of the password, in characters. (no maximum by default)
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Require a lowercase letter in the password. (not required by default)
+    pub fn require_lowercase(mut self, require: bool) -> Self {
+        self.require_lowercase = require;
+        self
+    }
+
+    /// Require an uppercase letter in the password. (not required by default)
+    pub fn require_uppercase(mut self, require: bool) -> Self {
+        self.require_uppercase = require;
+        self
+    }
+
+    /// Require a digit in the password. (not required by default)
+    pub fn require_digit(mut self, require: bool) -> Self {
+        self.require_digit = require;
+        self
+    }
+
+    /// Require a symbol in the password, a character which is not alphanumeric or whitespace. (not required by default)
+    pub fn require_symbol(mut self, require: bool) -> Self {
+        self.require_symbol = require;
+        self
+    }
+
+    /// Ban the given passwords, compared case-insensitively.
+    pub fn ban(mut self, passwords: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.banned.extend(
+            passwords
+                .into_iter()
+                .map(|password| password.as_ref().to_lowercase()),
+        );
+        self
+    }
+
+    /// Returns the reasons why the password violates the policy, empty if the password is valid.
+    /// Each reason completes the sentence "The password ...", e.g. "must contain a digit".
+    pub fn violations(&self, password: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        let len = password.chars().count();
+        if len == 0 {
+            violations.push("must not be empty".to_owned());
+        } else if len < self.min_len {
+            violations.push(format!(
+                "must be at least {} characters, found {len}",
+                self.min_len
+            ));
+        }
+        if let Some(max_len) = self.max_len {
+            if len > max_len {
+                violations.push(format!("must be at most {max_len} characters, found {len}"));
+            }
+        }
+        let mut require = |required: bool, class: &str, is_class: fn(char) -> bool| {
+            if required && !password.chars().any(is_class) {
+                violations.push(format!("must contain {class}"));
+            }
+        };
+        require(
+            self.require_lowercase,
+            "a lowercase letter",
+            char::is_lowercase,
+        );
+        require(
+            self.require_uppercase,
+            "an uppercase letter",
+            char::is_uppercase,
+        );
+        require(self.require_digit, "a digit", |ch| ch.is_ascii_digit());
+        require(self.require_symbol, "a symbol", |ch| {
+            !ch.is_alphanumeric() && !ch.is_whitespace()
+        });
+        if self.banned.contains(&password.to_lowercase()) {
+            violations.push("is too common".to_owned());
+        }
+        violations
+    }
+
+    /// Validate the password, returns [`SDKError::Validation`] with all the violations if the password is invalid.
+    pub fn validate(&self, password: &str) -> Result<(), SDKError> {
+        let violations = self.violations(password);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(SDKError::validation("password", violations.join(", ")))
+    }
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_len: 1,
+            max_len: None,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            banned: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the reason of the validation error of the field.
+    fn reason(field: &str, result: Result<impl Sized, SDKError>) -> String {
+        match result {
+            Err(SDKError::Validation {
+                field: err_field,
+                reason,
+            }) if err_field == field => reason,
+            Err(err) => panic!("unexpected error: {err}"),
+            Ok(_) => panic!("the {field} is valid"),
+        }
+    }
+
+    #[test]
+    fn title_is_trimmed() {
+        assert_eq!(
+            TitleRules::new().validate("  Buy milk \n").unwrap(),
+            "Buy milk"
+        );
+    }
+
+    #[test]
+    fn title_must_not_be_empty() {
+        let rules = TitleRules::new();
+        assert_eq!(reason("title", rules.validate("")), "must not be empty");
+        assert_eq!(
+            reason("title", rules.validate(" \t\n")),
+            "must not be empty"
+        );
+    }
+
+    #[test]
+    fn title_max_len_counts_characters() {
+        let rules = TitleRules::new().max_len(4);
+        assert_eq!(rules.validate(" café ").unwrap(), "café");
+        assert_eq!(
+            reason("title", rules.validate("cafés")),
+            "must be at most 4 characters, found 5"
+        );
+    }
+
+    #[test]
+    fn title_must_not_contain_control_characters() {
+        assert_eq!(
+            reason("title", TitleRules::new().validate("Buy\u{7}milk")),
+            "must not contain control characters, found U+0007 at character 4"
+        );
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn title_is_normalized() {
+        let decomposed = "cafe\u{301}";
+        assert_eq!(TitleRules::new().validate(decomposed).unwrap(), decomposed);
+        let rules = TitleRules::new().normalize(true).max_len(4);
+        assert_eq!(rules.validate(decomposed).unwrap(), "caf\u{e9}");
+    }
+
+    #[test]
+    fn default_username_rules() {
+        let rules = UsernameRules::new();
+        assert!(rules.validate("ada_lovelace").is_ok());
+        assert!(rules.validate("é").is_ok());
+        assert_eq!(reason("username", rules.validate("")), "must not be empty");
+        assert_eq!(
+            reason("username", rules.validate("ada lovelace")),
+            "must not contain the character U+0020 at character 4"
+        );
+        assert_eq!(
+            reason("username", rules.validate("ada\n")),
+            "must not contain the character U+000A at character 4"
+        );
+    }
+
+    #[test]
+    fn username_length_and_charset() {
+        let rules = UsernameRules::new()
+            .min_len(3)
+            .max_len(5)
+            .charset(|ch| ch.is_ascii_lowercase());
+        assert!(rules.validate("abc").is_ok());
+        assert!(rules.validate("abcde").is_ok());
+        assert_eq!(
+            reason("username", rules.validate("ab")),
+            "must be at least 3 characters, found 2"
+        );
+        assert_eq!(
+            reason("username", rules.validate("abcdef")),
+            "must be at most 5 characters, found 6"
+        );
+        assert_eq!(
+            reason("username", rules.validate("abC")),
+            "must not contain the character U+0043 at character 3"
+        );
+        // A zero minimum length still rejects the empty username
+        assert_eq!(
+            reason("username", UsernameRules::new().min_len(0).validate("")),
+            "must not be empty"
+        );
+    }
+
+    #[test]
+    fn default_password_policy() {
+        let policy = PasswordPolicy::new();
+        assert!(policy.violations("a").is_empty());
+        assert!(policy.validate("a").is_ok());
+        assert_eq!(policy.violations(""), ["must not be empty"]);
+    }
+
+    #[test]
+    fn password_violations_are_all_reported() {
+        let policy = PasswordPolicy::new()
+            .min_len(6)
+            .max_len(8)
+            .require_lowercase(true)
+            .require_uppercase(true)
+            .require_digit(true)
+            .require_symbol(true);
+        assert!(policy.validate("aB3$xy").is_ok());
+        assert_eq!(
+            policy.violations("abc"),
+            [
+                "must be at least 6 characters, found 3",
+                "must contain an uppercase letter",
+                "must contain a digit",
+                "must contain a symbol",
+            ]
+        );
+        assert_eq!(
+            policy.violations("ABCDEFGH1$"),
+            [
+                "must be at most 8 characters, found 10",
+                "must contain a lowercase letter",
+            ]
+        );
+        assert_eq!(
+            reason("password", policy.validate("")),
+            "must not be empty, must contain a lowercase letter, must contain an uppercase letter, \
+             must contain a digit, must contain a symbol"
+        );
+    }
+
+    #[test]
+    fn banned_passwords_are_case_insensitive() {
+        let policy = PasswordPolicy::new().ban(["Hunter2"]);
+        assert_eq!(policy.violations("hUNTER2"), ["is too common"]);
+        assert!(policy.violations("hunter3").is_empty());
+        let recommended = PasswordPolicy::recommended();
+        assert_eq!(recommended.violations("Password123"), ["is too common"]);
+        assert!(recommended.validate("Correct4Horse").is_ok());
+    }
+}